@@ -123,5 +123,5 @@ pub fn close_account_raw<'a>(
     **src_lamports_mut = 0;
 
     src_account_info.assign(&system_program::ID);
-    src_account_info.resize(0).map_err(Into::into)
+    src_account_info.resize(0)
 }
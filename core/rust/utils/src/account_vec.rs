@@ -0,0 +1,399 @@
+use std::{cell::Ref, marker::PhantomData, mem::size_of};
+
+use arrayref::array_ref;
+use bytemuck::Pod;
+use solana_program::{
+    account_info::AccountInfo,
+    entrypoint::{ProgramResult, MAX_PERMITTED_DATA_INCREASE},
+    msg,
+    program_error::ProgramError,
+};
+
+use crate::resize_or_reallocate_account_raw;
+
+/// Size of the `capacity: u32` and `len: u32` header preceding the elements.
+pub const ACCOUNT_VEC_HEADER_LEN: usize = 8;
+
+/// Capacity of an empty vector after its first push.
+const MIN_NON_ZERO_CAPACITY: usize = 4;
+
+/// Vector of `T` stored in account data at `offset`, as a little-endian
+/// `capacity: u32` and `len: u32` header followed by `capacity` elements.
+/// Elements are copied in and out, so `offset` doesn't need to be aligned.
+///
+/// Like `Vec`, removing elements keeps the capacity; call `shrink_to_fit` to
+/// give the rent back. Only a vector ending at the end of the account data
+/// can be resized.
+#[derive(Debug)]
+pub struct AccountVec<'a, 'info, T: Pod> {
+    account_info: &'a AccountInfo<'info>,
+    offset: usize,
+    capacity: usize,
+    len: usize,
+    _element: PhantomData<T>,
+}
+
+impl<'a, 'info, T: Pod> AccountVec<'a, 'info, T> {
+    /// Size of an element. Zero-sized elements are rejected at compile time.
+    const ELEMENT_LEN: usize = {
+        assert!(
+            size_of::<T>() > 0,
+            "AccountVec elements can't be zero-sized"
+        );
+        size_of::<T>()
+    };
+
+    /// Bytes taken by a vector with room for `capacity` elements.
+    pub const fn space(capacity: usize) -> usize {
+        ACCOUNT_VEC_HEADER_LEN + capacity * Self::ELEMENT_LEN
+    }
+
+    /// Writes an empty vector with room for `capacity` elements at `offset`.
+    /// The account data must already be large enough, see `space`.
+    pub fn init(
+        account_info: &'a AccountInfo<'info>,
+        offset: usize,
+        capacity: usize,
+    ) -> Result<Self, ProgramError> {
+        let capacity_header = u32::try_from(capacity).map_err(|_| ProgramError::InvalidArgument)?;
+        check_space(account_info, offset, Self::space(capacity))?;
+
+        let mut data = account_info.try_borrow_mut_data()?;
+        data[offset..offset + 4].copy_from_slice(&capacity_header.to_le_bytes());
+        data[offset + 4..offset + ACCOUNT_VEC_HEADER_LEN].copy_from_slice(&0u32.to_le_bytes());
+
+        Ok(Self {
+            account_info,
+            offset,
+            capacity,
+            len: 0,
+            _element: PhantomData,
+        })
+    }
+
+    /// Loads the vector at `offset`. Fails with `InvalidAccountData` if the
+    /// length exceeds the capacity or the elements don't fit in the account.
+    pub fn load(account_info: &'a AccountInfo<'info>, offset: usize) -> Result<Self, ProgramError> {
+        check_space(account_info, offset, ACCOUNT_VEC_HEADER_LEN)?;
+
+        let data = account_info.try_borrow_data()?;
+        let capacity = u32::from_le_bytes(*array_ref![data, offset, 4]) as usize;
+        let len = u32::from_le_bytes(*array_ref![data, offset + 4, 4]) as usize;
+        drop(data);
+
+        if len > capacity {
+            msg!("Vector length {} exceeds its capacity {}", len, capacity);
+            return Err(ProgramError::InvalidAccountData);
+        }
+        check_space(account_info, offset, Self::space(capacity))
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+
+        Ok(Self {
+            account_info,
+            offset,
+            capacity,
+            len,
+            _element: PhantomData,
+        })
+    }
+
+    /// len
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// is_empty
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Number of elements the vector can hold without resizing the account.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    fn element_offset(&self, index: usize) -> usize {
+        self.offset + ACCOUNT_VEC_HEADER_LEN + index * Self::ELEMENT_LEN
+    }
+
+    fn read(&self, data: &[u8], index: usize) -> T {
+        let start = self.element_offset(index);
+        bytemuck::pod_read_unaligned(&data[start..start + Self::ELEMENT_LEN])
+    }
+
+    fn write(&self, data: &mut [u8], index: usize, value: &T) {
+        let start = self.element_offset(index);
+        data[start..start + Self::ELEMENT_LEN].copy_from_slice(bytemuck::bytes_of(value));
+    }
+
+    fn set_len(&mut self, len: usize) -> ProgramResult {
+        let mut data = self.account_info.try_borrow_mut_data()?;
+        data[self.offset + 4..self.offset + ACCOUNT_VEC_HEADER_LEN]
+            .copy_from_slice(&(len as u32).to_le_bytes());
+        self.len = len;
+
+        Ok(())
+    }
+
+    fn check_index(&self, index: usize) -> ProgramResult {
+        if index >= self.len {
+            msg!(
+                "Vector index {} out of bounds, length is {}",
+                index,
+                self.len
+            );
+            return Err(ProgramError::InvalidArgument);
+        }
+        Ok(())
+    }
+
+    /// Element at `index`.
+    pub fn get(&self, index: usize) -> Result<T, ProgramError> {
+        self.check_index(index)?;
+        let data = self.account_info.try_borrow_data()?;
+
+        Ok(self.read(&data, index))
+    }
+
+    /// Overwrites the element at `index`.
+    pub fn set(&self, index: usize, value: &T) -> ProgramResult {
+        self.check_index(index)?;
+        let mut data = self.account_info.try_borrow_mut_data()?;
+        self.write(&mut data, index, value);
+
+        Ok(())
+    }
+
+    /// Iterates over copies of the elements. The account data stays borrowed
+    /// until the iterator is dropped.
+    pub fn iter(&self) -> Result<AccountVecIter<'a, T>, ProgramError> {
+        let start = self.element_offset(0);
+        let end = self.element_offset(self.len);
+        let data = Ref::map(self.account_info.try_borrow_data()?, |data| {
+            &data[start..end]
+        });
+
+        Ok(AccountVecIter {
+            data,
+            index: 0,
+            _element: PhantomData,
+        })
+    }
+
+    /// Resizes the account so the vector can hold `capacity` elements, with
+    /// `payer` funding the rent or receiving the refund.
+    fn resize(
+        &mut self,
+        capacity: usize,
+        payer: &AccountInfo<'info>,
+        system_program: &AccountInfo<'info>,
+    ) -> ProgramResult {
+        let capacity_header = u32::try_from(capacity).map_err(|_| ProgramError::InvalidArgument)?;
+        if self.element_offset(self.capacity) != self.account_info.data_len() {
+            msg!("Only a vector at the end of the account data can be resized");
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        resize_or_reallocate_account_raw(
+            self.account_info,
+            payer,
+            system_program,
+            self.offset + Self::space(capacity),
+        )?;
+        let mut data = self.account_info.try_borrow_mut_data()?;
+        data[self.offset..self.offset + 4].copy_from_slice(&capacity_header.to_le_bytes());
+        self.capacity = capacity;
+
+        Ok(())
+    }
+
+    /// Grows the account, if needed, so exactly `additional` more elements fit.
+    pub fn reserve(
+        &mut self,
+        additional: usize,
+        payer: &AccountInfo<'info>,
+        system_program: &AccountInfo<'info>,
+    ) -> ProgramResult {
+        let capacity = self
+            .len
+            .checked_add(additional)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        if capacity <= self.capacity {
+            return Ok(());
+        }
+        self.resize(capacity, payer, system_program)
+    }
+
+    /// Shrinks the account to the vector's length, refunding the rent to `payer`.
+    pub fn shrink_to_fit(
+        &mut self,
+        payer: &AccountInfo<'info>,
+        system_program: &AccountInfo<'info>,
+    ) -> ProgramResult {
+        if self.len == self.capacity {
+            return Ok(());
+        }
+        self.resize(self.len, payer, system_program)
+    }
+
+    /// Appends `value`. A full vector doubles its capacity, growing the account
+    /// by at most `MAX_PERMITTED_DATA_INCREASE` bytes at a time.
+    pub fn push(
+        &mut self,
+        value: &T,
+        payer: &AccountInfo<'info>,
+        system_program: &AccountInfo<'info>,
+    ) -> ProgramResult {
+        if self.len == self.capacity {
+            let max_growth = (MAX_PERMITTED_DATA_INCREASE / Self::ELEMENT_LEN).max(1);
+            let capacity = (self.capacity * 2)
+                .max(MIN_NON_ZERO_CAPACITY)
+                .min(self.capacity + max_growth);
+            self.resize(capacity, payer, system_program)?;
+        }
+        let mut data = self.account_info.try_borrow_mut_data()?;
+        self.write(&mut data, self.len, value);
+        drop(data);
+
+        self.set_len(self.len + 1)
+    }
+
+    /// Removes the last element.
+    pub fn pop(&mut self) -> Result<Option<T>, ProgramError> {
+        if self.len == 0 {
+            return Ok(None);
+        }
+        let value = self.get(self.len - 1)?;
+        self.set_len(self.len - 1)?;
+
+        Ok(Some(value))
+    }
+
+    /// Removes the element at `index`, replacing it with the last element.
+    pub fn swap_remove(&mut self, index: usize) -> Result<T, ProgramError> {
+        self.check_index(index)?;
+        let mut data = self.account_info.try_borrow_mut_data()?;
+        let value = self.read(&data, index);
+        let last = self.read(&data, self.len - 1);
+        self.write(&mut data, index, &last);
+        drop(data);
+
+        self.set_len(self.len - 1)?;
+        Ok(value)
+    }
+
+    /// Removes the element at `index`, shifting the following elements down.
+    pub fn remove(&mut self, index: usize) -> Result<T, ProgramError> {
+        self.check_index(index)?;
+        let mut data = self.account_info.try_borrow_mut_data()?;
+        let value = self.read(&data, index);
+        data.copy_within(
+            self.element_offset(index + 1)..self.element_offset(self.len),
+            self.element_offset(index),
+        );
+        drop(data);
+
+        self.set_len(self.len - 1)?;
+        Ok(value)
+    }
+}
+
+/// Iterator returned by `AccountVec::iter`.
+pub struct AccountVecIter<'a, T: Pod> {
+    data: Ref<'a, [u8]>,
+    index: usize,
+    _element: PhantomData<T>,
+}
+
+impl<T: Pod> Iterator for AccountVecIter<'_, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        let start = self.index * size_of::<T>();
+        let bytes = self.data.get(start..start + size_of::<T>())?;
+        self.index += 1;
+
+        Some(bytemuck::pod_read_unaligned(bytes))
+    }
+}
+
+fn check_space(account_info: &AccountInfo, offset: usize, space: usize) -> ProgramResult {
+    let end = offset
+        .checked_add(space)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    if end > account_info.data_len() {
+        msg!(
+            "Vector range {}..{} exceeds account data length {}",
+            offset,
+            end,
+            account_info.data_len()
+        );
+        return Err(ProgramError::AccountDataTooSmall);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_program::pubkey::Pubkey;
+
+    use super::*;
+
+    #[test]
+    fn push_remove_and_iterate() {
+        let key = Pubkey::new_unique();
+        let mut lamports = 0;
+        let mut data = vec![0; 3 + AccountVec::<u64>::space(4)];
+        let account_info =
+            AccountInfo::new(&key, false, true, &mut lamports, &mut data, &key, false);
+        let mut vec = AccountVec::<u64>::init(&account_info, 3, 4).unwrap();
+
+        for value in 1..=4 {
+            vec.push(&value, &account_info, &account_info).unwrap();
+        }
+        assert_eq!(vec.iter().unwrap().collect::<Vec<_>>(), vec![1, 2, 3, 4]);
+        assert_eq!(vec.swap_remove(0), Ok(1));
+        assert_eq!(vec.iter().unwrap().collect::<Vec<_>>(), vec![4, 2, 3]);
+        assert_eq!(vec.remove(0), Ok(4));
+        assert_eq!(vec.iter().unwrap().collect::<Vec<_>>(), vec![2, 3]);
+        vec.set(1, &7).unwrap();
+        assert_eq!(vec.get(1), Ok(7));
+        assert_eq!(vec.get(2), Err(ProgramError::InvalidArgument));
+        assert_eq!(vec.remove(2), Err(ProgramError::InvalidArgument));
+        assert_eq!(vec.pop(), Ok(Some(7)));
+
+        let vec = AccountVec::<u64>::load(&account_info, 3).unwrap();
+        assert_eq!((vec.len(), vec.capacity()), (1, 4));
+        assert_eq!(vec.iter().unwrap().collect::<Vec<_>>(), vec![2]);
+    }
+
+    #[test]
+    fn headers_are_validated() {
+        let key = Pubkey::new_unique();
+        let mut lamports = 0;
+        let mut data = vec![0; AccountVec::<u64>::space(2)];
+        let account_info =
+            AccountInfo::new(&key, false, true, &mut lamports, &mut data, &key, false);
+
+        assert_eq!(
+            AccountVec::<u64>::init(&account_info, 0, 3).unwrap_err(),
+            ProgramError::AccountDataTooSmall
+        );
+        AccountVec::<u64>::init(&account_info, 0, 2).unwrap();
+
+        account_info.try_borrow_mut_data().unwrap()[4] = 3;
+        assert_eq!(
+            AccountVec::<u64>::load(&account_info, 0).unwrap_err(),
+            ProgramError::InvalidAccountData
+        );
+        account_info.try_borrow_mut_data().unwrap()[..8].copy_from_slice(&[3, 0, 0, 0, 1, 0, 0, 0]);
+        assert_eq!(
+            AccountVec::<u64>::load(&account_info, 0).unwrap_err(),
+            ProgramError::InvalidAccountData
+        );
+        assert_eq!(
+            AccountVec::<u64>::load(&account_info, 17).unwrap_err(),
+            ProgramError::AccountDataTooSmall
+        );
+    }
+}
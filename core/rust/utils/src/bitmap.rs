@@ -0,0 +1,239 @@
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, msg, program_error::ProgramError,
+};
+
+use crate::resize_or_reallocate_account_raw;
+
+/// Returns the byte index and bit mask of `index`. Bits are numbered from the
+/// most significant bit of each byte, as in Token Metadata edition markers.
+fn get_bit(index: usize) -> (usize, u8) {
+    (index / 8, 0b1000_0000 >> (index % 8))
+}
+
+/// Bitmap over `len` bytes of an account's data starting at `offset`, e.g. to
+/// record claimed airdrop or allowlist indices. Bits are read and written in
+/// place; the view only remembers the byte range.
+#[derive(Clone, Debug)]
+pub struct ClaimBitmap<'a, 'info> {
+    account_info: &'a AccountInfo<'info>,
+    offset: usize,
+    len: usize,
+}
+
+impl<'a, 'info> ClaimBitmap<'a, 'info> {
+    /// Fails with `AccountDataTooSmall` if the range is outside the account data.
+    pub fn new(
+        account_info: &'a AccountInfo<'info>,
+        offset: usize,
+        len: usize,
+    ) -> Result<Self, ProgramError> {
+        let end = offset
+            .checked_add(len)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        if end > account_info.data_len() {
+            msg!(
+                "Bitmap range {}..{} exceeds account data length {}",
+                offset,
+                end,
+                account_info.data_len()
+            );
+            return Err(ProgramError::AccountDataTooSmall);
+        }
+
+        Ok(Self {
+            account_info,
+            offset,
+            len,
+        })
+    }
+
+    /// Bitmap over all of the account data from `offset` to the end. Only such
+    /// a bitmap can be grown.
+    pub fn to_end(
+        account_info: &'a AccountInfo<'info>,
+        offset: usize,
+    ) -> Result<Self, ProgramError> {
+        let len = account_info
+            .data_len()
+            .checked_sub(offset)
+            .ok_or(ProgramError::AccountDataTooSmall)?;
+        Self::new(account_info, offset, len)
+    }
+
+    /// Number of bits the bitmap can hold.
+    pub fn capacity(&self) -> usize {
+        self.len * 8
+    }
+
+    fn check_index(&self, index: usize) -> Result<(usize, u8), ProgramError> {
+        if index >= self.capacity() {
+            msg!(
+                "Bitmap index {} out of bounds, capacity is {}",
+                index,
+                self.capacity()
+            );
+            return Err(ProgramError::InvalidArgument);
+        }
+        let (byte, mask) = get_bit(index);
+        Ok((self.offset + byte, mask))
+    }
+
+    /// Returns true if the bit at `index` is set.
+    pub fn is_set(&self, index: usize) -> Result<bool, ProgramError> {
+        let (byte, mask) = self.check_index(index)?;
+        let data = self.account_info.try_borrow_data()?;
+
+        Ok(data[byte] & mask != 0)
+    }
+
+    /// Sets the bit at `index`. Returns false if it was already set, so a
+    /// claim can be rejected without a separate `is_set`.
+    pub fn set(&self, index: usize) -> Result<bool, ProgramError> {
+        let (byte, mask) = self.check_index(index)?;
+        let mut data = self.account_info.try_borrow_mut_data()?;
+        let was_set = data[byte] & mask != 0;
+        data[byte] |= mask;
+
+        Ok(!was_set)
+    }
+
+    /// Clears the bit at `index`. Returns false if it was not set.
+    pub fn unset(&self, index: usize) -> Result<bool, ProgramError> {
+        let (byte, mask) = self.check_index(index)?;
+        let mut data = self.account_info.try_borrow_mut_data()?;
+        let was_set = data[byte] & mask != 0;
+        data[byte] &= !mask;
+
+        Ok(was_set)
+    }
+
+    /// Index of the first clear bit, or `None` if the bitmap is full.
+    pub fn first_unset(&self) -> Result<Option<usize>, ProgramError> {
+        let data = self.account_info.try_borrow_data()?;
+        let bytes = &data[self.offset..self.offset + self.len];
+
+        Ok(bytes
+            .iter()
+            .position(|byte| *byte != u8::MAX)
+            .map(|byte| byte * 8 + bytes[byte].leading_ones() as usize))
+    }
+
+    /// Number of set bits.
+    pub fn count(&self) -> Result<usize, ProgramError> {
+        let data = self.account_info.try_borrow_data()?;
+
+        Ok(data[self.offset..self.offset + self.len]
+            .iter()
+            .map(|byte| byte.count_ones() as usize)
+            .sum())
+    }
+
+    /// Grows the account, with `payer` funding the rent, until the bitmap can
+    /// hold `index`. New bits are clear. Fails with `InvalidAccountData` if the
+    /// bitmap doesn't extend to the end of the account data.
+    pub fn grow_to_fit(
+        &mut self,
+        index: usize,
+        payer: &AccountInfo<'info>,
+        system_program: &AccountInfo<'info>,
+    ) -> ProgramResult {
+        if index < self.capacity() {
+            return Ok(());
+        }
+        if self.offset + self.len != self.account_info.data_len() {
+            msg!("Only a bitmap at the end of the account data can grow");
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        let len = get_bit(index).0 + 1;
+        resize_or_reallocate_account_raw(
+            self.account_info,
+            payer,
+            system_program,
+            self.offset + len,
+        )?;
+        self.len = len;
+
+        Ok(())
+    }
+
+    /// `set`, growing the account first if `index` exceeds the capacity.
+    pub fn set_or_grow(
+        &mut self,
+        index: usize,
+        payer: &AccountInfo<'info>,
+        system_program: &AccountInfo<'info>,
+    ) -> Result<bool, ProgramError> {
+        self.grow_to_fit(index, payer, system_program)?;
+        self.set(index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_program::pubkey::Pubkey;
+
+    use super::*;
+
+    #[test]
+    fn set_test_and_count() {
+        let key = Pubkey::new_unique();
+        let mut lamports = 0;
+        let mut data = vec![0xff, 0, 0, 0xff];
+        let account_info =
+            AccountInfo::new(&key, false, true, &mut lamports, &mut data, &key, false);
+        let bitmap = ClaimBitmap::new(&account_info, 1, 2).unwrap();
+
+        assert_eq!(bitmap.capacity(), 16);
+        assert_eq!(bitmap.count(), Ok(0));
+        assert_eq!(bitmap.first_unset(), Ok(Some(0)));
+        assert_eq!(bitmap.set(0), Ok(true));
+        assert_eq!(bitmap.set(0), Ok(false));
+        assert_eq!(bitmap.set(9), Ok(true));
+        assert_eq!(bitmap.is_set(9), Ok(true));
+        assert_eq!(bitmap.is_set(8), Ok(false));
+        assert_eq!(bitmap.count(), Ok(2));
+        assert_eq!(bitmap.first_unset(), Ok(Some(1)));
+        assert_eq!(bitmap.is_set(16), Err(ProgramError::InvalidArgument));
+        assert_eq!(bitmap.set(16), Err(ProgramError::InvalidArgument));
+
+        for index in 0..16 {
+            bitmap.set(index).unwrap();
+        }
+        assert_eq!(bitmap.first_unset(), Ok(None));
+        assert_eq!(bitmap.unset(3), Ok(true));
+        assert_eq!(bitmap.unset(3), Ok(false));
+        assert_eq!(bitmap.first_unset(), Ok(Some(3)));
+        assert_eq!(
+            *account_info.data.borrow(),
+            &[0xff, 0b1110_1111, 0xff, 0xff]
+        );
+    }
+
+    #[test]
+    fn range_is_checked() {
+        let key = Pubkey::new_unique();
+        let mut lamports = 0;
+        let mut data = vec![0; 4];
+        let account_info =
+            AccountInfo::new(&key, false, true, &mut lamports, &mut data, &key, false);
+
+        assert!(ClaimBitmap::new(&account_info, 2, 2).is_ok());
+        assert_eq!(
+            ClaimBitmap::new(&account_info, 3, 2).unwrap_err(),
+            ProgramError::AccountDataTooSmall
+        );
+        assert_eq!(
+            ClaimBitmap::new(&account_info, usize::MAX, 2).unwrap_err(),
+            ProgramError::ArithmeticOverflow
+        );
+        assert_eq!(
+            ClaimBitmap::to_end(&account_info, 1).unwrap().capacity(),
+            24
+        );
+        assert_eq!(
+            ClaimBitmap::to_end(&account_info, 5).unwrap_err(),
+            ProgramError::AccountDataTooSmall
+        );
+    }
+}
@@ -0,0 +1,70 @@
+use solana_program::{program_error::ProgramError, pubkey::Pubkey};
+
+/// Cursor over borsh-encoded account data, borrowing strings and byte slices
+/// from the input instead of allocating.
+pub(crate) struct BorshReader<'data> {
+    pub(crate) data: &'data [u8],
+}
+
+// Only Token Metadata accounts need the fixed-size readers.
+#[cfg_attr(not(feature = "metadata"), allow(dead_code))]
+impl<'data> BorshReader<'data> {
+    pub(crate) fn read_bytes(&mut self, len: usize) -> Result<&'data [u8], ProgramError> {
+        if self.data.len() < len {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        let (bytes, rest) = self.data.split_at(len);
+        self.data = rest;
+
+        Ok(bytes)
+    }
+
+    pub(crate) fn read_u8(&mut self) -> Result<u8, ProgramError> {
+        Ok(self.read_bytes(1)?[0])
+    }
+
+    pub(crate) fn read_bool(&mut self) -> Result<bool, ProgramError> {
+        match self.read_u8()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(ProgramError::InvalidAccountData),
+        }
+    }
+
+    pub(crate) fn read_u16(&mut self) -> Result<u16, ProgramError> {
+        Ok(u16::from_le_bytes(self.read_bytes(2)?.try_into().unwrap()))
+    }
+
+    pub(crate) fn read_u32(&mut self) -> Result<u32, ProgramError> {
+        Ok(u32::from_le_bytes(self.read_bytes(4)?.try_into().unwrap()))
+    }
+
+    pub(crate) fn read_u64(&mut self) -> Result<u64, ProgramError> {
+        Ok(u64::from_le_bytes(self.read_bytes(8)?.try_into().unwrap()))
+    }
+
+    pub(crate) fn read_pubkey(&mut self) -> Result<Pubkey, ProgramError> {
+        Ok(Pubkey::new_from_array(
+            self.read_bytes(32)?.try_into().unwrap(),
+        ))
+    }
+
+    pub(crate) fn read_str(&mut self) -> Result<&'data str, ProgramError> {
+        let len = self.read_u32()? as usize;
+
+        std::str::from_utf8(self.read_bytes(len)?).map_err(|_| ProgramError::InvalidAccountData)
+    }
+
+    /// Reads a borsh `Option` tag.
+    pub(crate) fn read_option(&mut self) -> Result<bool, ProgramError> {
+        self.read_bool()
+    }
+
+    /// Reads an `Option` tag that may be missing at the end of older accounts.
+    pub(crate) fn read_trailing_option(&mut self) -> Result<bool, ProgramError> {
+        if self.data.is_empty() {
+            return Ok(false);
+        }
+        self.read_option()
+    }
+}
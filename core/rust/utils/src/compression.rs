@@ -0,0 +1,365 @@
+use solana_keccak_hasher::hashv;
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, msg, program_error::ProgramError, pubkey,
+    pubkey::Pubkey,
+};
+
+use crate::{assert_owner_in, merkle::Node};
+
+pub const BUBBLEGUM_PROGRAM_ID: Pubkey = pubkey!("BGUMAp9Gq7iTEuizy4pqaxsTyUCBK68MDfK752saRPUY");
+
+/// spl-account-compression and its mpl-account-compression fork, which share
+/// the concurrent merkle tree layout.
+pub static ACCOUNT_COMPRESSION_PROGRAM_IDS: [Pubkey; 2] = [
+    pubkey!("cmtDvXumGCrqC1Age74AVPhSRVXJMd8PJS91L8KbNCK"),
+    pubkey!("mcmt6YrQEMKw8Mw43FmpRLmf7BqRnFMKmAcbxE3xkAW"),
+];
+
+/// Size of the tree account header: account type(1), header version(1),
+/// max_buffer_size(4), max_depth(4), authority(32), creation_slot(8), padding(6).
+const TREE_HEADER_LEN: usize = 56;
+
+/// sequence_number(8), active_index(8), buffer_size(8).
+const TREE_COUNTERS_LEN: usize = 24;
+
+/// Derives the asset id of the leaf minted at `nonce` in a Bubblegum tree.
+pub fn get_asset_id(merkle_tree: &Pubkey, nonce: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"asset", merkle_tree.as_ref(), &nonce.to_le_bytes()],
+        &BUBBLEGUM_PROGRAM_ID,
+    )
+    .0
+}
+
+/// Hashes a Bubblegum `LeafSchema::V1`.
+pub fn hash_leaf(
+    id: &Pubkey,
+    owner: &Pubkey,
+    delegate: &Pubkey,
+    nonce: u64,
+    data_hash: &Node,
+    creator_hash: &Node,
+) -> Node {
+    hashv(&[
+        &[1],
+        id.as_ref(),
+        owner.as_ref(),
+        delegate.as_ref(),
+        &nonce.to_le_bytes(),
+        data_hash,
+        creator_hash,
+    ])
+    .to_bytes()
+}
+
+/// Computes the leaf data hash from the borsh-encoded `MetadataArgs`, as
+/// received in Bubblegum instruction data.
+pub fn hash_metadata_args(metadata_args: &[u8], seller_fee_basis_points: u16) -> Node {
+    let metadata_args_hash = hashv(&[metadata_args]).to_bytes();
+
+    hashv(&[&metadata_args_hash, &seller_fee_basis_points.to_le_bytes()]).to_bytes()
+}
+
+/// Computes the leaf creator hash from `(address, verified, share)` creators.
+pub fn hash_creators(creators: &[(Pubkey, bool, u8)]) -> Node {
+    let mut data = Vec::with_capacity(creators.len() * 34);
+    for (address, verified, share) in creators {
+        data.extend_from_slice(address.as_ref());
+        data.push(*verified as u8);
+        data.push(*share);
+    }
+
+    hashv(&[&data]).to_bytes()
+}
+
+/// Hash of an empty subtree of the given height.
+fn empty_node(level: u32) -> Node {
+    let mut node = [0; 32];
+    for _ in 0..level {
+        node = hashv(&[&node, &node]).to_bytes();
+    }
+    node
+}
+
+/// Recomputes the root from a leaf, its index and the sibling nodes from the
+/// leaf up.
+pub fn compute_root(leaf: &Node, index: u32, proof: &[Node]) -> Node {
+    let mut node = *leaf;
+    for (level, sibling) in proof.iter().enumerate() {
+        node = if (index >> level) & 1 == 0 {
+            hashv(&[&node, sibling])
+        } else {
+            hashv(&[sibling, &node])
+        }
+        .to_bytes();
+    }
+    node
+}
+
+/// View over a concurrent merkle tree account.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MerkleTreeRef<'data> {
+    /// max_depth
+    pub max_depth: u32,
+    /// max_buffer_size
+    pub max_buffer_size: u32,
+    /// Root after the latest change.
+    pub root: Node,
+    canopy: &'data [u8],
+}
+
+impl<'data> MerkleTreeRef<'data> {
+    pub fn unpack(data: &'data [u8]) -> Result<Self, ProgramError> {
+        let header = data
+            .get(..TREE_HEADER_LEN)
+            .ok_or(ProgramError::InvalidAccountData)?;
+        // CompressionAccountType::ConcurrentMerkleTree and header version V1.
+        if header[..2] != [1, 0] {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        let max_buffer_size = u32::from_le_bytes(header[2..6].try_into().unwrap());
+        let max_depth = u32::from_le_bytes(header[6..10].try_into().unwrap());
+        if max_depth == 0 || max_depth > 30 {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        let depth = max_depth as usize;
+        let change_log_len = 32 + 32 * depth + 8;
+        let rightmost_proof_len = 32 * depth + 32 + 8;
+        let tree_len =
+            TREE_COUNTERS_LEN + change_log_len * max_buffer_size as usize + rightmost_proof_len;
+        let tree = data
+            .get(TREE_HEADER_LEN..TREE_HEADER_LEN + tree_len)
+            .ok_or(ProgramError::InvalidAccountData)?;
+        let active_index = u64::from_le_bytes(tree[8..16].try_into().unwrap());
+        if active_index >= max_buffer_size as u64 {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        let root_offset = TREE_COUNTERS_LEN + change_log_len * active_index as usize;
+
+        Ok(Self {
+            max_depth,
+            max_buffer_size,
+            root: tree[root_offset..root_offset + 32].try_into().unwrap(),
+            canopy: &data[TREE_HEADER_LEN + tree_len..],
+        })
+    }
+
+    /// Number of levels below the root cached in the account, so proofs can
+    /// leave out their top nodes.
+    pub fn canopy_depth(&self) -> Result<u32, ProgramError> {
+        if !self.canopy.len().is_multiple_of(32) {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        // A canopy of depth d holds the 2^(d+1) - 2 nodes below the root.
+        let nodes = self.canopy.len() / 32;
+        let depth = (nodes + 2).ilog2() - 1;
+        if (1 << (depth + 1)) - 2 != nodes || depth > self.max_depth {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        Ok(depth)
+    }
+
+    /// Completes a proof truncated by the canopy with the cached nodes. Fails
+    /// with `InvalidArgument` if `index` is outside the tree or the proof is too
+    /// long, or too short for the canopy.
+    pub fn fill_proof_from_canopy(
+        &self,
+        index: u32,
+        proof: &[Node],
+    ) -> Result<Vec<Node>, ProgramError> {
+        let max_depth = self.max_depth as usize;
+        if u64::from(index) >= 1 << self.max_depth
+            || proof.len() > max_depth
+            || proof.len() + (self.canopy_depth()? as usize) < max_depth
+        {
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        // Nodes are numbered as in a binary heap: root 1, children 2n and 2n + 1,
+        // and the canopy stores nodes 2 and up.
+        let leaf_node = (1u64 << self.max_depth) + index as u64;
+        let mut full_proof = proof.to_vec();
+        for height in proof.len()..max_depth {
+            let sibling = (((leaf_node >> height) ^ 1) - 2) as usize * 32;
+            let node: Node = self.canopy[sibling..sibling + 32].try_into().unwrap();
+            full_proof.push(if node == [0; 32] {
+                empty_node(height as u32)
+            } else {
+                node
+            });
+        }
+
+        Ok(full_proof)
+    }
+
+    /// Returns true if the leaf is at `index` in the current tree.
+    pub fn verify_leaf(
+        &self,
+        leaf: &Node,
+        index: u32,
+        proof: &[Node],
+    ) -> Result<bool, ProgramError> {
+        let proof = self.fill_proof_from_canopy(index, proof)?;
+
+        Ok(compute_root(leaf, index, &proof) == self.root)
+    }
+}
+
+/// Verifies that the leaf is at `index` of the tree, checking the tree account
+/// owner. The proof nodes are the keys of `proof_accounts`, usually the
+/// instruction's remaining accounts, and may omit nodes cached in the canopy.
+/// Only proofs against the current root are accepted.
+pub fn verify_compressed_leaf(
+    merkle_tree_info: &AccountInfo,
+    leaf: &Node,
+    index: u32,
+    proof_accounts: &[AccountInfo],
+) -> ProgramResult {
+    assert_owner_in(
+        merkle_tree_info,
+        &ACCOUNT_COMPRESSION_PROGRAM_IDS,
+        ProgramError::IllegalOwner,
+    )?;
+    let proof: Vec<Node> = proof_accounts
+        .iter()
+        .map(|account| account.key.to_bytes())
+        .collect();
+
+    let data = merkle_tree_info.try_borrow_data()?;
+    if MerkleTreeRef::unpack(&data)?.verify_leaf(leaf, index, &proof)? {
+        Ok(())
+    } else {
+        msg!("Leaf {} does not match the tree root", index);
+        Err(ProgramError::InvalidArgument)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Builds every level of a full tree, from the leaves up to the root.
+    fn tree_levels(leaves: &[Node]) -> Vec<Vec<Node>> {
+        let mut levels = vec![leaves.to_vec()];
+        while levels.last().unwrap().len() > 1 {
+            let level = levels
+                .last()
+                .unwrap()
+                .chunks(2)
+                .map(|pair| hashv(&[&pair[0], &pair[1]]).to_bytes())
+                .collect();
+            levels.push(level);
+        }
+        levels
+    }
+
+    fn proof(levels: &[Vec<Node>], index: usize) -> Vec<Node> {
+        levels[..levels.len() - 1]
+            .iter()
+            .enumerate()
+            .map(|(height, level)| level[(index >> height) ^ 1])
+            .collect()
+    }
+
+    /// Lays out a tree account with a single changelog entry and a canopy.
+    fn tree_account(levels: &[Vec<Node>], canopy_depth: usize) -> Vec<u8> {
+        let max_depth = levels.len() - 1;
+        let mut data = vec![1, 0];
+        data.extend_from_slice(&1u32.to_le_bytes());
+        data.extend_from_slice(&(max_depth as u32).to_le_bytes());
+        data.resize(TREE_HEADER_LEN + TREE_COUNTERS_LEN, 0);
+        data.extend_from_slice(&levels[max_depth][0]);
+        data.resize(data.len() + 32 * max_depth + 8 + 32 * max_depth + 32 + 8, 0);
+        for level in levels[max_depth - canopy_depth..max_depth].iter().rev() {
+            for node in level {
+                data.extend_from_slice(node);
+            }
+        }
+        data
+    }
+
+    #[test]
+    fn leaf_and_data_hashes() {
+        let creator = Pubkey::new_unique();
+        assert_eq!(
+            hash_creators(&[(creator, true, 100)]),
+            hashv(&[creator.as_ref(), &[1, 100]]).to_bytes()
+        );
+        assert_eq!(hash_creators(&[]), hashv(&[&[]]).to_bytes());
+
+        let metadata_args_hash = hashv(&[b"args"]).to_bytes();
+        assert_eq!(
+            hash_metadata_args(b"args", 500),
+            hashv(&[&metadata_args_hash, &[244, 1]]).to_bytes()
+        );
+
+        let tree = Pubkey::new_unique();
+        let (id, owner) = (get_asset_id(&tree, 7), Pubkey::new_unique());
+        assert_ne!(id, get_asset_id(&tree, 8));
+        let leaf = hash_leaf(&id, &owner, &owner, 7, &[1; 32], &[2; 32]);
+        assert_ne!(leaf, hash_leaf(&id, &owner, &owner, 8, &[1; 32], &[2; 32]));
+    }
+
+    #[test]
+    fn verify_proofs_with_canopy() {
+        let mut leaves: Vec<Node> = (0..8u8).map(|leaf| [leaf + 1; 32]).collect();
+        // Leaves 6 and 7 were never set, so the canopy caches an empty node.
+        leaves[6] = [0; 32];
+        leaves[7] = [0; 32];
+        let levels = tree_levels(&leaves);
+
+        for canopy_depth in 0..=3 {
+            let mut data = tree_account(&levels, canopy_depth);
+            if canopy_depth >= 2 {
+                // Node 7 in heap order, the parent of leaves 6 and 7.
+                let canopy_start = data.len() - 32 * ((1 << (canopy_depth + 1)) - 2);
+                let empty_parent = canopy_start + 32 * (7 - 2);
+                assert_eq!(data[empty_parent..empty_parent + 32], empty_node(1));
+                data[empty_parent..empty_parent + 32].fill(0);
+            }
+            let tree = MerkleTreeRef::unpack(&data).unwrap();
+            assert_eq!(tree.max_depth, 3);
+            assert_eq!(tree.canopy_depth(), Ok(canopy_depth as u32));
+
+            for (index, leaf) in leaves.iter().enumerate() {
+                let full_proof = proof(&levels, index);
+                let truncated = &full_proof[..3 - canopy_depth];
+                assert_eq!(tree.verify_leaf(leaf, index as u32, truncated), Ok(true));
+                assert_eq!(
+                    tree.verify_leaf(&[0xff; 32], index as u32, truncated),
+                    Ok(false)
+                );
+                assert_eq!(tree.verify_leaf(leaf, index as u32, &full_proof), Ok(true));
+            }
+            if canopy_depth < 3 {
+                assert!(tree.verify_leaf(&leaves[0], 0, &[]).is_err());
+            }
+            assert_eq!(
+                tree.fill_proof_from_canopy(8, &[]),
+                Err(ProgramError::InvalidArgument)
+            );
+            assert_eq!(
+                tree.fill_proof_from_canopy(u32::MAX, &proof(&levels, 0)),
+                Err(ProgramError::InvalidArgument)
+            );
+        }
+    }
+
+    #[test]
+    fn unpack_rejects_invalid_trees() {
+        let levels = tree_levels(&[[1; 32], [2; 32]]);
+        let data = tree_account(&levels, 0);
+
+        assert!(MerkleTreeRef::unpack(&data[..data.len() - 1]).is_err());
+        let mut uninitialized = data.clone();
+        uninitialized[0] = 0;
+        assert!(MerkleTreeRef::unpack(&uninitialized).is_err());
+        let mut bad_canopy = data;
+        bad_canopy.extend_from_slice(&[0; 32]);
+        assert!(MerkleTreeRef::unpack(&bad_canopy)
+            .unwrap()
+            .canopy_depth()
+            .is_err());
+    }
+}
@@ -0,0 +1,225 @@
+use solana_program::pubkey::Pubkey;
+
+/// 100% expressed in basis points.
+pub const MAX_BASIS_POINTS: u16 = 10_000;
+
+/// How to round the result of a division.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Rounding {
+    /// Towards zero, the payer keeps the dust.
+    #[default]
+    Down,
+    /// Away from zero, the payee gets the dust.
+    Up,
+    /// To the nearest integer, halves rounded up.
+    HalfUp,
+    /// To the nearest integer, halves rounded to the even neighbour.
+    HalfEven,
+}
+
+/// Computes `amount * numerator / denominator` with a 128-bit intermediate and
+/// the given rounding. Returns `None` on division by zero or if the result
+/// doesn't fit in a u64.
+pub fn checked_mul_div(
+    amount: u64,
+    numerator: u64,
+    denominator: u64,
+    rounding: Rounding,
+) -> Option<u64> {
+    if denominator == 0 {
+        return None;
+    }
+    let product = (amount as u128) * (numerator as u128);
+    let denominator = denominator as u128;
+    let quotient = product / denominator;
+    let remainder = product % denominator;
+
+    let round_up = match rounding {
+        Rounding::Down => false,
+        Rounding::Up => remainder > 0,
+        Rounding::HalfUp => remainder * 2 >= denominator,
+        Rounding::HalfEven => {
+            remainder * 2 > denominator || (remainder * 2 == denominator && quotient % 2 == 1)
+        }
+    };
+
+    u64::try_from(quotient + round_up as u128).ok()
+}
+
+/// Computes `amount * basis_points / 10_000`. Returns `None` if `basis_points`
+/// is above 100%.
+pub fn checked_basis_points(amount: u64, basis_points: u16, rounding: Rounding) -> Option<u64> {
+    if basis_points > MAX_BASIS_POINTS {
+        return None;
+    }
+    checked_mul_div(
+        amount,
+        basis_points as u64,
+        MAX_BASIS_POINTS as u64,
+        rounding,
+    )
+}
+
+/// Amount owed to a single recipient.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Payout {
+    /// Wallet, or token account, receiving the payout.
+    pub recipient: Pubkey,
+    /// amount
+    pub amount: u64,
+}
+
+/// Payouts to make out of a total, plus what is left for the remaining party,
+/// e.g. the seller after royalties. Each payout maps to one
+/// `spl_token_transfer_checked` or one lamport transfer.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PayoutPlan {
+    /// payouts
+    pub payouts: Vec<Payout>,
+    /// Part of the total not covered by `payouts`.
+    pub remainder: u64,
+}
+
+impl PayoutPlan {
+    /// Sum of all payouts.
+    pub fn total_paid(&self) -> u64 {
+        self.payouts.iter().map(|payout| payout.amount).sum()
+    }
+}
+
+/// Splits `amount` among recipients in proportion to their weights. Every unit
+/// is assigned: shares are rounded down and the leftover units go one each to
+/// the recipients with the largest rounding loss, ties broken by position.
+/// Returns `None` if the recipients are empty or all weights are zero.
+pub fn split_by_weight(amount: u64, recipients: &[(Pubkey, u64)]) -> Option<Vec<Payout>> {
+    let total_weight: u128 = recipients.iter().map(|(_, weight)| *weight as u128).sum();
+    if total_weight == 0 {
+        return None;
+    }
+
+    let mut payouts = Vec::with_capacity(recipients.len());
+    let mut losses = Vec::with_capacity(recipients.len());
+    let mut assigned: u64 = 0;
+    for (index, (recipient, weight)) in recipients.iter().enumerate() {
+        let product = (amount as u128) * (*weight as u128);
+        // Each share is at most `amount`, so it always fits.
+        let share = (product / total_weight) as u64;
+        assigned += share;
+        payouts.push(Payout {
+            recipient: *recipient,
+            amount: share,
+        });
+        losses.push((product % total_weight, index));
+    }
+
+    // Sorting by descending loss then ascending index keeps the assignment deterministic.
+    losses.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+    let leftover = (amount - assigned) as usize;
+    for (_, index) in losses.into_iter().take(leftover) {
+        payouts[index].amount += 1;
+    }
+
+    Some(payouts)
+}
+
+/// Plans a Metaplex-style royalty payout: `seller_fee_basis_points` of the
+/// price is split among creators by share, the rest goes to the seller as the
+/// plan's remainder.
+pub fn plan_royalty_payouts(
+    price: u64,
+    seller_fee_basis_points: u16,
+    creators: &[(Pubkey, u8)],
+    rounding: Rounding,
+) -> Option<PayoutPlan> {
+    let royalty = checked_basis_points(price, seller_fee_basis_points, rounding)?;
+    if royalty == 0 || creators.is_empty() {
+        return Some(PayoutPlan {
+            payouts: vec![],
+            remainder: price,
+        });
+    }
+
+    let weights: Vec<(Pubkey, u64)> = creators
+        .iter()
+        .map(|(creator, share)| (*creator, *share as u64))
+        .collect();
+    let payouts = split_by_weight(royalty, &weights)?;
+
+    Some(PayoutPlan {
+        payouts,
+        remainder: price - royalty,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rounding_modes() {
+        assert_eq!(checked_mul_div(5, 1, 2, Rounding::Down), Some(2));
+        assert_eq!(checked_mul_div(5, 1, 2, Rounding::Up), Some(3));
+        assert_eq!(checked_mul_div(5, 1, 2, Rounding::HalfUp), Some(3));
+        assert_eq!(checked_mul_div(5, 1, 2, Rounding::HalfEven), Some(2));
+        assert_eq!(checked_mul_div(7, 1, 2, Rounding::HalfEven), Some(4));
+        assert_eq!(checked_mul_div(4, 1, 3, Rounding::HalfUp), Some(1));
+        assert_eq!(checked_mul_div(4, 1, 3, Rounding::Up), Some(2));
+        assert_eq!(checked_mul_div(1, 1, 0, Rounding::Down), None);
+        assert_eq!(checked_mul_div(u64::MAX, 2, 1, Rounding::Down), None);
+        assert_eq!(
+            checked_mul_div(u64::MAX, u64::MAX, u64::MAX, Rounding::Up),
+            Some(u64::MAX)
+        );
+    }
+
+    #[test]
+    fn basis_points() {
+        assert_eq!(checked_basis_points(1_000, 250, Rounding::Down), Some(25));
+        assert_eq!(checked_basis_points(999, 250, Rounding::Down), Some(24));
+        assert_eq!(checked_basis_points(999, 250, Rounding::Up), Some(25));
+        assert_eq!(
+            checked_basis_points(u64::MAX, 10_000, Rounding::Down),
+            Some(u64::MAX)
+        );
+        assert_eq!(checked_basis_points(1, 10_001, Rounding::Down), None);
+    }
+
+    #[test]
+    fn split_assigns_every_unit_deterministically() {
+        let (a, b, c) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+
+        let payouts = split_by_weight(100, &[(a, 1), (b, 1), (c, 1)]).unwrap();
+        let amounts: Vec<u64> = payouts.iter().map(|payout| payout.amount).collect();
+        assert_eq!(amounts, vec![34, 33, 33]);
+
+        let payouts = split_by_weight(10, &[(a, 15), (b, 50), (c, 35)]).unwrap();
+        let amounts: Vec<u64> = payouts.iter().map(|payout| payout.amount).collect();
+        assert_eq!(amounts, vec![2, 5, 3]);
+
+        let payouts = split_by_weight(u64::MAX, &[(a, u64::MAX), (b, u64::MAX)]).unwrap();
+        assert_eq!(payouts[0].amount + payouts[1].amount, u64::MAX);
+
+        assert_eq!(split_by_weight(10, &[(a, 0)]), None);
+        assert_eq!(split_by_weight(10, &[]), None);
+    }
+
+    #[test]
+    fn royalty_plan() {
+        let (a, b) = (Pubkey::new_unique(), Pubkey::new_unique());
+
+        let plan =
+            plan_royalty_payouts(1_000_001, 500, &[(a, 70), (b, 30)], Rounding::Down).unwrap();
+        assert_eq!(plan.total_paid(), 50_000);
+        assert_eq!(plan.payouts[0].amount, 35_000);
+        assert_eq!(plan.payouts[1].amount, 15_000);
+        assert_eq!(plan.remainder, 950_001);
+
+        let plan = plan_royalty_payouts(1_000, 0, &[(a, 100)], Rounding::Down).unwrap();
+        assert!(plan.payouts.is_empty());
+        assert_eq!(plan.remainder, 1_000);
+    }
+}
@@ -4,7 +4,18 @@ pub use misc::*;
 
 mod account;
 mod assertions;
+#[cfg(any(feature = "metadata", feature = "spl-token"))]
+mod borsh_reader;
 mod misc;
 
+pub mod account_vec;
+pub mod bitmap;
+pub mod compression;
+pub mod fees;
+pub mod merkle;
+
+#[cfg(feature = "metadata")]
+pub mod metadata;
+
 #[cfg(feature = "spl-token")]
 pub mod token;
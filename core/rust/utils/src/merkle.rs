@@ -0,0 +1,143 @@
+use solana_keccak_hasher::hashv;
+use solana_program::pubkey::Pubkey;
+
+pub type Node = [u8; 32];
+
+/// Prefixes keeping leaves and inner nodes in separate domains, so an inner
+/// node can never be passed off as a leaf.
+pub const LEAF_PREFIX: &[u8] = &[0];
+pub const NODE_PREFIX: &[u8] = &[1];
+
+/// Hashes the concatenated leaf fields. Fields must have a fixed size, or be
+/// length-prefixed, for the encoding to be unambiguous.
+pub fn hash_leaf(fields: &[&[u8]]) -> Node {
+    let mut values = Vec::with_capacity(fields.len() + 1);
+    values.push(LEAF_PREFIX);
+    values.extend_from_slice(fields);
+
+    hashv(&values).to_bytes()
+}
+
+/// Leaf of a `wallet + amount` allowlist or airdrop.
+pub fn hash_allowlist_leaf(wallet: &Pubkey, amount: u64) -> Node {
+    hash_leaf(&[wallet.as_ref(), &amount.to_le_bytes()])
+}
+
+/// Hashes two sibling nodes. The pair is sorted first, so proofs don't need
+/// to encode which side each sibling is on.
+pub fn hash_node(a: &Node, b: &Node) -> Node {
+    let (left, right) = if a <= b { (a, b) } else { (b, a) };
+
+    hashv(&[NODE_PREFIX, left, right]).to_bytes()
+}
+
+/// Returns true if the leaf hash and the proof rebuild `root`.
+pub fn verify_merkle_proof(leaf: &Node, proof: &[Node], root: &Node) -> bool {
+    let computed = proof
+        .iter()
+        .fold(*leaf, |node, sibling| hash_node(&node, sibling));
+
+    computed == *root
+}
+
+/// Off-chain tree builder producing roots and proofs for `verify_merkle_proof`.
+/// A node without a sibling is carried up to the next level unchanged.
+#[cfg(all(feature = "merkle-tree", not(target_os = "solana")))]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MerkleTree {
+    levels: Vec<Vec<Node>>,
+}
+
+#[cfg(all(feature = "merkle-tree", not(target_os = "solana")))]
+impl MerkleTree {
+    /// Builds the tree over leaf hashes, e.g. from `hash_allowlist_leaf`.
+    pub fn new(leaves: Vec<Node>) -> Self {
+        let mut levels = vec![leaves];
+        while levels.last().unwrap().len() > 1 {
+            let level = levels
+                .last()
+                .unwrap()
+                .chunks(2)
+                .map(|pair| match pair {
+                    [left, right] => hash_node(left, right),
+                    [node] => *node,
+                    _ => unreachable!(),
+                })
+                .collect();
+            levels.push(level);
+        }
+
+        Self { levels }
+    }
+
+    /// Root of the tree, all zeros if it has no leaves.
+    pub fn root(&self) -> Node {
+        self.levels
+            .last()
+            .and_then(|level| level.first())
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Proof for the leaf at `index`, or `None` if it is out of bounds.
+    pub fn proof(&self, index: usize) -> Option<Vec<Node>> {
+        if index >= self.levels[0].len() {
+            return None;
+        }
+
+        let proof = self
+            .levels
+            .iter()
+            .enumerate()
+            .filter_map(|(height, level)| level.get((index >> height) ^ 1))
+            .copied()
+            .collect();
+        Some(proof)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn verify_two_leaf_proof() {
+        let wallet = Pubkey::new_unique();
+        let leaf = hash_allowlist_leaf(&wallet, 5);
+        let sibling = hash_allowlist_leaf(&Pubkey::new_unique(), 1);
+        let root = hash_node(&sibling, &leaf);
+
+        assert_eq!(leaf, hash_leaf(&[wallet.as_ref(), &5u64.to_le_bytes()]));
+        assert!(verify_merkle_proof(&leaf, &[sibling], &root));
+        assert!(verify_merkle_proof(&sibling, &[leaf], &root));
+        assert!(!verify_merkle_proof(
+            &hash_allowlist_leaf(&wallet, 6),
+            &[sibling],
+            &root
+        ));
+        // Leaves and inner nodes over the same bytes hash differently.
+        assert_ne!(
+            hash_leaf(&[leaf.as_ref(), sibling.as_ref()]),
+            hash_node(&leaf, &sibling)
+        );
+    }
+
+    #[cfg(all(feature = "merkle-tree", not(target_os = "solana")))]
+    #[test]
+    fn tree_proofs_verify() {
+        for len in 0..10u64 {
+            let leaves: Vec<Node> = (0..len)
+                .map(|amount| hash_allowlist_leaf(&Pubkey::new_unique(), amount))
+                .collect();
+            let tree = MerkleTree::new(leaves.clone());
+            let root = tree.root();
+
+            for (index, leaf) in leaves.iter().enumerate() {
+                let proof = tree.proof(index).unwrap();
+                assert!(verify_merkle_proof(leaf, &proof, &root), "{len} {index}");
+                assert!(!verify_merkle_proof(&[0; 32], &proof, &root));
+            }
+            assert_eq!(tree.proof(len as usize), None);
+        }
+    }
+}
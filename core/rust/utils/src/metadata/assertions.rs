@@ -0,0 +1,230 @@
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, msg, program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+use super::{
+    get_key, get_token_standard, Key, MetadataRef, TokenStandard, PREFIX, TOKEN_METADATA_PROGRAM_ID,
+};
+use crate::{assert_derivation, assert_owned_by};
+
+/// Asserts that
+/// * the account is owned by Token Metadata
+/// * its `Key` byte is `key`
+///
+/// Returns `ProgramError::IllegalOwner` or `ProgramError::InvalidAccountData`.
+pub fn assert_token_metadata_account(account_info: &AccountInfo, key: Key) -> ProgramResult {
+    assert_owned_by(
+        account_info,
+        &TOKEN_METADATA_PROGRAM_ID,
+        ProgramError::IllegalOwner,
+    )?;
+
+    if get_key(account_info)? != key {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    Ok(())
+}
+
+/// Asserts that the account is the Metadata PDA of `mint`, owned by Token
+/// Metadata and initialized as `MetadataV1`. Returns the PDA bump.
+pub fn assert_metadata_account(
+    metadata_info: &AccountInfo,
+    mint: &Pubkey,
+) -> Result<u8, ProgramError> {
+    let bump = assert_derivation(
+        &TOKEN_METADATA_PROGRAM_ID,
+        metadata_info,
+        &[
+            PREFIX.as_bytes(),
+            TOKEN_METADATA_PROGRAM_ID.as_ref(),
+            mint.as_ref(),
+        ],
+        ProgramError::InvalidSeeds,
+    )?;
+    assert_token_metadata_account(metadata_info, Key::MetadataV1)?;
+
+    Ok(bump)
+}
+
+/// Asserts that the account is the Metadata of `mint` and that its token
+/// standard is one of `token_standards`. Metadata without a token standard
+/// never matches.
+pub fn assert_token_standard(
+    metadata_info: &AccountInfo,
+    mint: &Pubkey,
+    token_standards: &[TokenStandard],
+) -> ProgramResult {
+    assert_metadata_account(metadata_info, mint)?;
+
+    match get_token_standard(metadata_info)? {
+        Some(token_standard) if token_standards.contains(&token_standard) => Ok(()),
+        token_standard => {
+            msg!("Unexpected token standard {:?}", token_standard);
+            Err(ProgramError::InvalidAccountData)
+        }
+    }
+}
+
+/// Asserts that `metadata_info` is the Metadata of `mint_info` and that it
+/// belongs to the verified collection `collection_mint`.
+pub fn assert_verified_collection_member(
+    metadata_info: &AccountInfo,
+    mint_info: &AccountInfo,
+    collection_mint: &Pubkey,
+) -> ProgramResult {
+    assert_metadata_account(metadata_info, mint_info.key)?;
+
+    let data = metadata_info.try_borrow_data()?;
+    match MetadataRef::unpack(&data)?.collection {
+        Some(collection) if collection.key == *collection_mint && collection.verified => Ok(()),
+        Some(collection) if collection.key == *collection_mint => {
+            msg!("Collection {} is not verified", collection_mint);
+            Err(ProgramError::InvalidAccountData)
+        }
+        _ => {
+            msg!("{} is not a member of {}", mint_info.key, collection_mint);
+            Err(ProgramError::InvalidAccountData)
+        }
+    }
+}
+
+/// Asserts that `metadata_info` is the Metadata of `mint` and that `creator` is
+/// one of its verified creators.
+pub fn assert_verified_creator(
+    metadata_info: &AccountInfo,
+    mint: &Pubkey,
+    creator: &Pubkey,
+) -> ProgramResult {
+    assert_metadata_account(metadata_info, mint)?;
+
+    let data = metadata_info.try_borrow_data()?;
+    if MetadataRef::unpack(&data)?
+        .creators()
+        .any(|metadata_creator| metadata_creator.address == *creator && metadata_creator.verified)
+    {
+        Ok(())
+    } else {
+        msg!("{} is not a verified creator", creator);
+        Err(ProgramError::InvalidAccountData)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metadata::{find_metadata_address, tests::metadata_data, Collection, Creator};
+
+    #[test]
+    fn verified_collection_and_creator() {
+        let mint = Pubkey::new_unique();
+        let collection_mint = Pubkey::new_unique();
+        let creators = [
+            Creator {
+                address: Pubkey::new_unique(),
+                verified: true,
+                share: 60,
+            },
+            Creator {
+                address: Pubkey::new_unique(),
+                verified: false,
+                share: 40,
+            },
+        ];
+        let (metadata_key, _) = find_metadata_address(&mint);
+        let mut data = metadata_data(
+            &mint,
+            &creators,
+            None,
+            Some(Collection {
+                verified: true,
+                key: collection_mint,
+            }),
+        );
+        let mut metadata_lamports = 0;
+        let metadata_info = AccountInfo::new(
+            &metadata_key,
+            false,
+            false,
+            &mut metadata_lamports,
+            &mut data,
+            &TOKEN_METADATA_PROGRAM_ID,
+            false,
+        );
+        let mut mint_lamports = 0;
+        let mint_info = AccountInfo::new(
+            &mint,
+            false,
+            false,
+            &mut mint_lamports,
+            &mut [],
+            &TOKEN_METADATA_PROGRAM_ID,
+            false,
+        );
+
+        assert!(
+            assert_verified_collection_member(&metadata_info, &mint_info, &collection_mint).is_ok()
+        );
+        assert!(assert_verified_collection_member(
+            &metadata_info,
+            &mint_info,
+            &Pubkey::new_unique()
+        )
+        .is_err());
+        assert!(assert_verified_creator(&metadata_info, &mint, &creators[0].address).is_ok());
+        assert!(assert_verified_creator(&metadata_info, &mint, &creators[1].address).is_err());
+
+        let other_mint = Pubkey::new_unique();
+        let mut other_mint_lamports = 0;
+        let other_mint_info = AccountInfo::new(
+            &other_mint,
+            false,
+            false,
+            &mut other_mint_lamports,
+            &mut [],
+            &TOKEN_METADATA_PROGRAM_ID,
+            false,
+        );
+        assert_eq!(
+            assert_verified_collection_member(&metadata_info, &other_mint_info, &collection_mint),
+            Err(ProgramError::InvalidSeeds)
+        );
+        assert_eq!(
+            assert_verified_creator(&metadata_info, &other_mint, &creators[0].address),
+            Err(ProgramError::InvalidSeeds)
+        );
+        assert_eq!(
+            assert_token_standard(&metadata_info, &mint, &[TokenStandard::NonFungible]),
+            Err(ProgramError::InvalidAccountData)
+        );
+        assert_eq!(
+            assert_token_standard(&metadata_info, &other_mint, &[TokenStandard::NonFungible]),
+            Err(ProgramError::InvalidSeeds)
+        );
+
+        let mut unverified = metadata_data(
+            &mint,
+            &creators,
+            None,
+            Some(Collection {
+                verified: false,
+                key: collection_mint,
+            }),
+        );
+        let mut unverified_lamports = 0;
+        let unverified_info = AccountInfo::new(
+            &metadata_key,
+            false,
+            false,
+            &mut unverified_lamports,
+            &mut unverified,
+            &TOKEN_METADATA_PROGRAM_ID,
+            false,
+        );
+        assert!(
+            assert_verified_collection_member(&unverified_info, &mint_info, &collection_mint)
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,1917 @@
+use solana_program::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    instruction::{AccountMeta, Instruction},
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+use super::{Collection, CollectionDetails, Creator, TokenStandard, TOKEN_METADATA_PROGRAM_ID};
+
+/// `MetadataInstruction` discriminators of the instructions wrapped below.
+mod discriminator {
+    pub const BURN: u8 = 41;
+    pub const CREATE: u8 = 42;
+    pub const MINT: u8 = 43;
+    pub const DELEGATE: u8 = 44;
+    pub const REVOKE: u8 = 45;
+    pub const LOCK: u8 = 46;
+    pub const UNLOCK: u8 = 47;
+    pub const TRANSFER: u8 = 49;
+    pub const UPDATE: u8 = 50;
+    pub const VERIFY: u8 = 52;
+    pub const UNVERIFY: u8 = 53;
+}
+
+/// Builds a Token Metadata instruction, returning it with the account infos
+/// to invoke it with. Each account is given with its writable and signer
+/// flags; missing optional accounts are replaced by the program id, as Token
+/// Metadata expects.
+fn token_metadata_instruction<'a>(
+    accounts: &[(Option<&AccountInfo<'a>>, bool, bool)],
+    data: Vec<u8>,
+) -> (Instruction, Vec<AccountInfo<'a>>) {
+    let mut metas = Vec::with_capacity(accounts.len());
+    let mut account_infos = Vec::with_capacity(accounts.len() + 1);
+    for (account, is_writable, is_signer) in accounts {
+        match account {
+            Some(account) => {
+                metas.push(AccountMeta {
+                    pubkey: *account.key,
+                    is_signer: *is_signer || account.is_signer,
+                    is_writable: *is_writable,
+                });
+                account_infos.push((*account).clone());
+            }
+            None => metas.push(AccountMeta::new_readonly(TOKEN_METADATA_PROGRAM_ID, false)),
+        }
+    }
+
+    let instruction = Instruction {
+        program_id: TOKEN_METADATA_PROGRAM_ID,
+        accounts: metas,
+        data,
+    };
+    (instruction, account_infos)
+}
+
+/// Invokes an instruction built by `token_metadata_instruction`.
+fn invoke_token_metadata<'a>(
+    token_metadata_program: &AccountInfo<'a>,
+    (instruction, mut account_infos): (Instruction, Vec<AccountInfo<'a>>),
+    signer_seeds: &[Option<&[&[u8]]>],
+) -> ProgramResult {
+    if *token_metadata_program.key != TOKEN_METADATA_PROGRAM_ID {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    account_infos.push(token_metadata_program.clone());
+
+    let seeds: Vec<&[&[u8]]> = signer_seeds.iter().flatten().copied().collect();
+    invoke_signed(&instruction, &account_infos, seeds.as_slice())
+}
+
+fn write_str(data: &mut Vec<u8>, value: &str) {
+    data.extend_from_slice(&(value.len() as u32).to_le_bytes());
+    data.extend_from_slice(value.as_bytes());
+}
+
+fn write_creators(data: &mut Vec<u8>, creators: Option<&[Creator]>) {
+    match creators {
+        Some(creators) => {
+            data.push(1);
+            data.extend_from_slice(&(creators.len() as u32).to_le_bytes());
+            for creator in creators {
+                data.extend_from_slice(creator.address.as_ref());
+                data.push(creator.verified as u8);
+                data.push(creator.share);
+            }
+        }
+        None => data.push(0),
+    }
+}
+
+fn write_pubkey_option(data: &mut Vec<u8>, value: Option<&Pubkey>) {
+    match value {
+        Some(value) => {
+            data.push(1);
+            data.extend_from_slice(value.as_ref());
+        }
+        None => data.push(0),
+    }
+}
+
+/// Number of prints a master edition allows.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PrintSupply {
+    Zero,
+    Limited(u64),
+    Unlimited,
+}
+
+/// Asset fields set by `CreateV1`.
+#[derive(Clone, Copy, Debug)]
+pub struct AssetData<'b> {
+    /// name
+    pub name: &'b str,
+    /// symbol
+    pub symbol: &'b str,
+    /// uri
+    pub uri: &'b str,
+    /// seller_fee_basis_points
+    pub seller_fee_basis_points: u16,
+    /// creators
+    pub creators: Option<&'b [Creator]>,
+    /// primary_sale_happened
+    pub primary_sale_happened: bool,
+    /// is_mutable
+    pub is_mutable: bool,
+    /// token_standard
+    pub token_standard: TokenStandard,
+    /// Unverified collection, verify it afterwards with `mpl_token_metadata_verify_collection`.
+    pub collection: Option<Collection>,
+    /// Only for collection parents.
+    pub collection_details: Option<CollectionDetails>,
+    /// Authorization rule set of a programmable NFT.
+    pub rule_set: Option<Pubkey>,
+}
+
+/// Creates the Metadata, and the master edition for non-fungibles, with
+/// `CreateV1`. The mint is created too if it has no data yet.
+pub fn mpl_token_metadata_create_v1(params: MetadataCreateV1Params<'_, '_>) -> ProgramResult {
+    invoke_token_metadata(
+        &params.token_metadata_program,
+        create_v1_instruction(&params),
+        &[
+            params.mint_signer_seeds,
+            params.authority_signer_seeds,
+            params.payer_signer_seeds,
+        ],
+    )
+}
+
+fn create_v1_instruction<'a>(
+    params: &MetadataCreateV1Params<'a, '_>,
+) -> (Instruction, Vec<AccountInfo<'a>>) {
+    let MetadataCreateV1Params {
+        metadata,
+        master_edition,
+        mint,
+        mint_signer_seeds,
+        authority,
+        payer,
+        update_authority,
+        asset_data,
+        decimals,
+        print_supply,
+        system_program,
+        sysvar_instructions,
+        token_program,
+        ..
+    } = params;
+
+    let mut data = vec![discriminator::CREATE, 0];
+    write_str(&mut data, asset_data.name);
+    write_str(&mut data, asset_data.symbol);
+    write_str(&mut data, asset_data.uri);
+    data.extend_from_slice(&asset_data.seller_fee_basis_points.to_le_bytes());
+    write_creators(&mut data, asset_data.creators);
+    data.push(asset_data.primary_sale_happened as u8);
+    data.push(asset_data.is_mutable as u8);
+    data.push(asset_data.token_standard as u8);
+    match asset_data.collection {
+        Some(collection) => {
+            data.extend_from_slice(&[1, collection.verified as u8]);
+            data.extend_from_slice(collection.key.as_ref());
+        }
+        None => data.push(0),
+    }
+    // uses
+    data.push(0);
+    match asset_data.collection_details {
+        Some(CollectionDetails::V1 { size }) => {
+            data.extend_from_slice(&[1, 0]);
+            data.extend_from_slice(&size.to_le_bytes());
+        }
+        Some(CollectionDetails::V2) => data.extend_from_slice(&[1, 1, 0, 0, 0, 0, 0, 0, 0, 0]),
+        None => data.push(0),
+    }
+    write_pubkey_option(&mut data, asset_data.rule_set.as_ref());
+    match decimals {
+        Some(decimals) => data.extend_from_slice(&[1, *decimals]),
+        None => data.push(0),
+    }
+    match print_supply {
+        Some(PrintSupply::Zero) => data.extend_from_slice(&[1, 0]),
+        Some(PrintSupply::Limited(max_supply)) => {
+            data.extend_from_slice(&[1, 1]);
+            data.extend_from_slice(&max_supply.to_le_bytes());
+        }
+        Some(PrintSupply::Unlimited) => data.extend_from_slice(&[1, 2]),
+        None => data.push(0),
+    }
+
+    token_metadata_instruction(
+        &[
+            (Some(metadata), true, false),
+            (master_edition.as_ref(), true, false),
+            (Some(mint), true, mint_signer_seeds.is_some()),
+            (Some(authority), false, true),
+            (Some(payer), true, true),
+            (Some(update_authority), false, false),
+            (Some(system_program), false, false),
+            (Some(sysvar_instructions), false, false),
+            (Some(token_program), false, false),
+        ],
+        data,
+    )
+}
+
+/// MetadataCreateV1Params
+pub struct MetadataCreateV1Params<'a: 'b, 'b> {
+    /// metadata
+    pub metadata: AccountInfo<'a>,
+    /// Required for non-fungibles.
+    pub master_edition: Option<AccountInfo<'a>>,
+    /// Must sign, directly or via `mint_signer_seeds`, if it doesn't exist yet.
+    pub mint: AccountInfo<'a>,
+    /// mint_signer_seeds
+    pub mint_signer_seeds: Option<&'b [&'b [u8]]>,
+    /// Mint authority.
+    pub authority: AccountInfo<'a>,
+    /// authority_signer_seeds
+    pub authority_signer_seeds: Option<&'b [&'b [u8]]>,
+    /// payer
+    pub payer: AccountInfo<'a>,
+    /// payer_signer_seeds
+    pub payer_signer_seeds: Option<&'b [&'b [u8]]>,
+    /// update_authority
+    pub update_authority: AccountInfo<'a>,
+    /// asset_data
+    pub asset_data: AssetData<'b>,
+    /// Only used when the mint is created.
+    pub decimals: Option<u8>,
+    /// Required for non-fungibles.
+    pub print_supply: Option<PrintSupply>,
+    /// system_program
+    pub system_program: AccountInfo<'a>,
+    /// sysvar_instructions
+    pub sysvar_instructions: AccountInfo<'a>,
+    /// token_program
+    pub token_program: AccountInfo<'a>,
+    /// token_metadata_program
+    pub token_metadata_program: AccountInfo<'a>,
+}
+
+/// Mints `amount` tokens with `MintV1`, creating the token account as an ATA
+/// of `token_owner` if needed. Programmable NFTs also need the token record.
+pub fn mpl_token_metadata_mint_v1(params: MetadataMintV1Params<'_, '_>) -> ProgramResult {
+    invoke_token_metadata(
+        &params.token_metadata_program,
+        mint_v1_instruction(&params),
+        &[params.authority_signer_seeds, params.payer_signer_seeds],
+    )
+}
+
+fn mint_v1_instruction<'a>(
+    params: &MetadataMintV1Params<'a, '_>,
+) -> (Instruction, Vec<AccountInfo<'a>>) {
+    let MetadataMintV1Params {
+        token,
+        token_owner,
+        metadata,
+        master_edition,
+        token_record,
+        mint,
+        authority,
+        delegate_record,
+        payer,
+        amount,
+        system_program,
+        sysvar_instructions,
+        token_program,
+        associated_token_program,
+        authorization_rules_program,
+        authorization_rules,
+        ..
+    } = params;
+
+    let mut data = vec![discriminator::MINT, 0];
+    data.extend_from_slice(&amount.to_le_bytes());
+    // authorization_data
+    data.push(0);
+
+    token_metadata_instruction(
+        &[
+            (Some(token), true, false),
+            (token_owner.as_ref(), false, false),
+            (Some(metadata), false, false),
+            (master_edition.as_ref(), false, false),
+            (token_record.as_ref(), true, false),
+            (Some(mint), true, false),
+            (Some(authority), false, true),
+            (delegate_record.as_ref(), false, false),
+            (Some(payer), true, true),
+            (Some(system_program), false, false),
+            (Some(sysvar_instructions), false, false),
+            (Some(token_program), false, false),
+            (Some(associated_token_program), false, false),
+            (authorization_rules_program.as_ref(), false, false),
+            (authorization_rules.as_ref(), false, false),
+        ],
+        data,
+    )
+}
+
+/// MetadataMintV1Params
+pub struct MetadataMintV1Params<'a: 'b, 'b> {
+    /// Destination token account.
+    pub token: AccountInfo<'a>,
+    /// Required if `token` has to be created.
+    pub token_owner: Option<AccountInfo<'a>>,
+    /// metadata
+    pub metadata: AccountInfo<'a>,
+    /// master_edition
+    pub master_edition: Option<AccountInfo<'a>>,
+    /// Required for programmable NFTs.
+    pub token_record: Option<AccountInfo<'a>>,
+    /// mint
+    pub mint: AccountInfo<'a>,
+    /// Mint authority, or update authority for non-fungibles.
+    pub authority: AccountInfo<'a>,
+    /// authority_signer_seeds
+    pub authority_signer_seeds: Option<&'b [&'b [u8]]>,
+    /// Metadata delegate record when `authority` is a delegate.
+    pub delegate_record: Option<AccountInfo<'a>>,
+    /// payer
+    pub payer: AccountInfo<'a>,
+    /// payer_signer_seeds
+    pub payer_signer_seeds: Option<&'b [&'b [u8]]>,
+    /// amount
+    pub amount: u64,
+    /// system_program
+    pub system_program: AccountInfo<'a>,
+    /// sysvar_instructions
+    pub sysvar_instructions: AccountInfo<'a>,
+    /// token_program
+    pub token_program: AccountInfo<'a>,
+    /// associated_token_program
+    pub associated_token_program: AccountInfo<'a>,
+    /// authorization_rules_program
+    pub authorization_rules_program: Option<AccountInfo<'a>>,
+    /// authorization_rules
+    pub authorization_rules: Option<AccountInfo<'a>>,
+    /// token_metadata_program
+    pub token_metadata_program: AccountInfo<'a>,
+}
+
+/// Transfers `amount` tokens with `TransferV1`, which also handles the token
+/// records and rule set checks of programmable NFTs.
+pub fn mpl_token_metadata_transfer_v1(params: MetadataTransferV1Params<'_, '_>) -> ProgramResult {
+    invoke_token_metadata(
+        &params.token_metadata_program,
+        transfer_v1_instruction(&params),
+        &[params.authority_signer_seeds, params.payer_signer_seeds],
+    )
+}
+
+fn transfer_v1_instruction<'a>(
+    params: &MetadataTransferV1Params<'a, '_>,
+) -> (Instruction, Vec<AccountInfo<'a>>) {
+    let MetadataTransferV1Params {
+        token,
+        token_owner,
+        destination_token,
+        destination_owner,
+        mint,
+        metadata,
+        edition,
+        owner_token_record,
+        destination_token_record,
+        authority,
+        payer,
+        amount,
+        system_program,
+        sysvar_instructions,
+        token_program,
+        associated_token_program,
+        authorization_rules_program,
+        authorization_rules,
+        ..
+    } = params;
+
+    let mut data = vec![discriminator::TRANSFER, 0];
+    data.extend_from_slice(&amount.to_le_bytes());
+    // authorization_data
+    data.push(0);
+
+    token_metadata_instruction(
+        &[
+            (Some(token), true, false),
+            (Some(token_owner), false, false),
+            (Some(destination_token), true, false),
+            (Some(destination_owner), false, false),
+            (Some(mint), false, false),
+            (Some(metadata), true, false),
+            (edition.as_ref(), false, false),
+            (owner_token_record.as_ref(), true, false),
+            (destination_token_record.as_ref(), true, false),
+            (Some(authority), false, true),
+            (Some(payer), true, true),
+            (Some(system_program), false, false),
+            (Some(sysvar_instructions), false, false),
+            (Some(token_program), false, false),
+            (Some(associated_token_program), false, false),
+            (authorization_rules_program.as_ref(), false, false),
+            (authorization_rules.as_ref(), false, false),
+        ],
+        data,
+    )
+}
+
+/// MetadataTransferV1Params
+pub struct MetadataTransferV1Params<'a: 'b, 'b> {
+    /// Source token account.
+    pub token: AccountInfo<'a>,
+    /// token_owner
+    pub token_owner: AccountInfo<'a>,
+    /// Destination ATA, created if needed.
+    pub destination_token: AccountInfo<'a>,
+    /// destination_owner
+    pub destination_owner: AccountInfo<'a>,
+    /// mint
+    pub mint: AccountInfo<'a>,
+    /// metadata
+    pub metadata: AccountInfo<'a>,
+    /// edition
+    pub edition: Option<AccountInfo<'a>>,
+    /// Required for programmable NFTs.
+    pub owner_token_record: Option<AccountInfo<'a>>,
+    /// Required for programmable NFTs.
+    pub destination_token_record: Option<AccountInfo<'a>>,
+    /// Owner or delegate of `token`.
+    pub authority: AccountInfo<'a>,
+    /// authority_signer_seeds
+    pub authority_signer_seeds: Option<&'b [&'b [u8]]>,
+    /// payer
+    pub payer: AccountInfo<'a>,
+    /// payer_signer_seeds
+    pub payer_signer_seeds: Option<&'b [&'b [u8]]>,
+    /// amount
+    pub amount: u64,
+    /// system_program
+    pub system_program: AccountInfo<'a>,
+    /// sysvar_instructions
+    pub sysvar_instructions: AccountInfo<'a>,
+    /// token_program
+    pub token_program: AccountInfo<'a>,
+    /// associated_token_program
+    pub associated_token_program: AccountInfo<'a>,
+    /// authorization_rules_program
+    pub authorization_rules_program: Option<AccountInfo<'a>>,
+    /// authorization_rules
+    pub authorization_rules: Option<AccountInfo<'a>>,
+    /// token_metadata_program
+    pub token_metadata_program: AccountInfo<'a>,
+}
+
+/// Burns `amount` tokens with `BurnV1`, closing the metadata, edition and token
+/// accounts when the whole supply of a non-fungible is burned.
+pub fn mpl_token_metadata_burn_v1(params: MetadataBurnV1Params<'_, '_>) -> ProgramResult {
+    invoke_token_metadata(
+        &params.token_metadata_program,
+        burn_v1_instruction(&params),
+        &[params.authority_signer_seeds],
+    )
+}
+
+fn burn_v1_instruction<'a>(
+    params: &MetadataBurnV1Params<'a, '_>,
+) -> (Instruction, Vec<AccountInfo<'a>>) {
+    let MetadataBurnV1Params {
+        authority,
+        collection_metadata,
+        metadata,
+        edition,
+        mint,
+        token,
+        master_edition,
+        master_edition_mint,
+        master_edition_token,
+        edition_marker,
+        token_record,
+        amount,
+        system_program,
+        sysvar_instructions,
+        token_program,
+        ..
+    } = params;
+
+    let mut data = vec![discriminator::BURN, 0];
+    data.extend_from_slice(&amount.to_le_bytes());
+
+    token_metadata_instruction(
+        &[
+            (Some(authority), true, true),
+            (collection_metadata.as_ref(), true, false),
+            (Some(metadata), true, false),
+            (edition.as_ref(), true, false),
+            (Some(mint), true, false),
+            (Some(token), true, false),
+            (master_edition.as_ref(), true, false),
+            (master_edition_mint.as_ref(), false, false),
+            (master_edition_token.as_ref(), false, false),
+            (edition_marker.as_ref(), true, false),
+            (token_record.as_ref(), true, false),
+            (Some(system_program), false, false),
+            (Some(sysvar_instructions), false, false),
+            (Some(token_program), false, false),
+        ],
+        data,
+    )
+}
+
+/// MetadataBurnV1Params
+pub struct MetadataBurnV1Params<'a: 'b, 'b> {
+    /// Token owner or delegate, receives the reclaimed rent.
+    pub authority: AccountInfo<'a>,
+    /// authority_signer_seeds
+    pub authority_signer_seeds: Option<&'b [&'b [u8]]>,
+    /// Required if the asset is a verified member of a sized collection.
+    pub collection_metadata: Option<AccountInfo<'a>>,
+    /// metadata
+    pub metadata: AccountInfo<'a>,
+    /// Master edition or print edition of the asset.
+    pub edition: Option<AccountInfo<'a>>,
+    /// mint
+    pub mint: AccountInfo<'a>,
+    /// token
+    pub token: AccountInfo<'a>,
+    /// Parent master edition, only when burning a print.
+    pub master_edition: Option<AccountInfo<'a>>,
+    /// master_edition_mint
+    pub master_edition_mint: Option<AccountInfo<'a>>,
+    /// master_edition_token
+    pub master_edition_token: Option<AccountInfo<'a>>,
+    /// edition_marker
+    pub edition_marker: Option<AccountInfo<'a>>,
+    /// Required for programmable NFTs.
+    pub token_record: Option<AccountInfo<'a>>,
+    /// amount
+    pub amount: u64,
+    /// system_program
+    pub system_program: AccountInfo<'a>,
+    /// sysvar_instructions
+    pub sysvar_instructions: AccountInfo<'a>,
+    /// token_program
+    pub token_program: AccountInfo<'a>,
+    /// token_metadata_program
+    pub token_metadata_program: AccountInfo<'a>,
+}
+
+/// Fields of `Data` set by `UpdateV1`.
+#[derive(Clone, Copy, Debug)]
+pub struct UpdateData<'b> {
+    /// name
+    pub name: &'b str,
+    /// symbol
+    pub symbol: &'b str,
+    /// uri
+    pub uri: &'b str,
+    /// seller_fee_basis_points
+    pub seller_fee_basis_points: u16,
+    /// creators
+    pub creators: Option<&'b [Creator]>,
+}
+
+/// Updates the metadata with `UpdateV1` as the update authority. `None` fields
+/// are left unchanged; the collection, uses and rule set are never modified.
+pub fn mpl_token_metadata_update_v1(params: MetadataUpdateV1Params<'_, '_>) -> ProgramResult {
+    invoke_token_metadata(
+        &params.token_metadata_program,
+        update_v1_instruction(&params),
+        &[params.authority_signer_seeds, params.payer_signer_seeds],
+    )
+}
+
+fn update_v1_instruction<'a>(
+    params: &MetadataUpdateV1Params<'a, '_>,
+) -> (Instruction, Vec<AccountInfo<'a>>) {
+    let MetadataUpdateV1Params {
+        authority,
+        delegate_record,
+        token,
+        mint,
+        metadata,
+        edition,
+        payer,
+        new_update_authority,
+        data: update_data,
+        primary_sale_happened,
+        is_mutable,
+        system_program,
+        sysvar_instructions,
+        authorization_rules_program,
+        authorization_rules,
+        ..
+    } = params;
+
+    let mut data = vec![discriminator::UPDATE, 0];
+    write_pubkey_option(&mut data, new_update_authority.as_ref());
+    match update_data {
+        Some(update_data) => {
+            data.push(1);
+            write_str(&mut data, update_data.name);
+            write_str(&mut data, update_data.symbol);
+            write_str(&mut data, update_data.uri);
+            data.extend_from_slice(&update_data.seller_fee_basis_points.to_le_bytes());
+            write_creators(&mut data, update_data.creators);
+        }
+        None => data.push(0),
+    }
+    for value in [*primary_sale_happened, *is_mutable] {
+        match value {
+            Some(value) => data.extend_from_slice(&[1, value as u8]),
+            None => data.push(0),
+        }
+    }
+    // collection, collection_details, uses and rule_set toggles left unchanged,
+    // then no authorization_data.
+    data.extend_from_slice(&[0, 0, 0, 0, 0]);
+
+    token_metadata_instruction(
+        &[
+            (Some(authority), false, true),
+            (delegate_record.as_ref(), false, false),
+            (token.as_ref(), false, false),
+            (Some(mint), false, false),
+            (Some(metadata), true, false),
+            (edition.as_ref(), false, false),
+            (Some(payer), true, true),
+            (Some(system_program), false, false),
+            (Some(sysvar_instructions), false, false),
+            (authorization_rules_program.as_ref(), false, false),
+            (authorization_rules.as_ref(), false, false),
+        ],
+        data,
+    )
+}
+
+/// MetadataUpdateV1Params
+pub struct MetadataUpdateV1Params<'a: 'b, 'b> {
+    /// Update authority or delegate.
+    pub authority: AccountInfo<'a>,
+    /// authority_signer_seeds
+    pub authority_signer_seeds: Option<&'b [&'b [u8]]>,
+    /// delegate_record
+    pub delegate_record: Option<AccountInfo<'a>>,
+    /// token
+    pub token: Option<AccountInfo<'a>>,
+    /// mint
+    pub mint: AccountInfo<'a>,
+    /// metadata
+    pub metadata: AccountInfo<'a>,
+    /// edition
+    pub edition: Option<AccountInfo<'a>>,
+    /// payer
+    pub payer: AccountInfo<'a>,
+    /// payer_signer_seeds
+    pub payer_signer_seeds: Option<&'b [&'b [u8]]>,
+    /// new_update_authority
+    pub new_update_authority: Option<Pubkey>,
+    /// data
+    pub data: Option<UpdateData<'b>>,
+    /// primary_sale_happened
+    pub primary_sale_happened: Option<bool>,
+    /// is_mutable
+    pub is_mutable: Option<bool>,
+    /// system_program
+    pub system_program: AccountInfo<'a>,
+    /// sysvar_instructions
+    pub sysvar_instructions: AccountInfo<'a>,
+    /// authorization_rules_program
+    pub authorization_rules_program: Option<AccountInfo<'a>>,
+    /// authorization_rules
+    pub authorization_rules: Option<AccountInfo<'a>>,
+    /// token_metadata_program
+    pub token_metadata_program: AccountInfo<'a>,
+}
+
+/// Verifies the metadata as a member of its collection with `Verify`, signed
+/// by the collection update authority or a collection delegate.
+pub fn mpl_token_metadata_verify_collection(
+    params: MetadataVerifyCollectionParams<'_, '_>,
+) -> ProgramResult {
+    invoke_token_metadata(
+        &params.token_metadata_program,
+        verify_collection_instruction(&params),
+        &[params.authority_signer_seeds],
+    )
+}
+
+fn verify_collection_instruction<'a>(
+    params: &MetadataVerifyCollectionParams<'a, '_>,
+) -> (Instruction, Vec<AccountInfo<'a>>) {
+    let MetadataVerifyCollectionParams {
+        authority,
+        delegate_record,
+        metadata,
+        collection_mint,
+        collection_metadata,
+        collection_master_edition,
+        system_program,
+        sysvar_instructions,
+        ..
+    } = params;
+
+    token_metadata_instruction(
+        &[
+            (Some(authority), false, true),
+            (delegate_record.as_ref(), false, false),
+            (Some(metadata), true, false),
+            (Some(collection_mint), false, false),
+            (Some(collection_metadata), true, false),
+            (Some(collection_master_edition), false, false),
+            (Some(system_program), false, false),
+            (Some(sysvar_instructions), false, false),
+        ],
+        // VerificationArgs::CollectionV1
+        vec![discriminator::VERIFY, 1],
+    )
+}
+
+/// MetadataVerifyCollectionParams
+pub struct MetadataVerifyCollectionParams<'a: 'b, 'b> {
+    /// Collection update authority or delegate.
+    pub authority: AccountInfo<'a>,
+    /// authority_signer_seeds
+    pub authority_signer_seeds: Option<&'b [&'b [u8]]>,
+    /// delegate_record
+    pub delegate_record: Option<AccountInfo<'a>>,
+    /// Metadata of the collection member.
+    pub metadata: AccountInfo<'a>,
+    /// collection_mint
+    pub collection_mint: AccountInfo<'a>,
+    /// collection_metadata
+    pub collection_metadata: AccountInfo<'a>,
+    /// collection_master_edition
+    pub collection_master_edition: AccountInfo<'a>,
+    /// system_program
+    pub system_program: AccountInfo<'a>,
+    /// sysvar_instructions
+    pub sysvar_instructions: AccountInfo<'a>,
+    /// token_metadata_program
+    pub token_metadata_program: AccountInfo<'a>,
+}
+
+/// Reverts `mpl_token_metadata_verify_collection` with `Unverify`.
+pub fn mpl_token_metadata_unverify_collection(
+    params: MetadataUnverifyCollectionParams<'_, '_>,
+) -> ProgramResult {
+    invoke_token_metadata(
+        &params.token_metadata_program,
+        unverify_collection_instruction(&params),
+        &[params.authority_signer_seeds],
+    )
+}
+
+fn unverify_collection_instruction<'a>(
+    params: &MetadataUnverifyCollectionParams<'a, '_>,
+) -> (Instruction, Vec<AccountInfo<'a>>) {
+    let MetadataUnverifyCollectionParams {
+        authority,
+        delegate_record,
+        metadata,
+        collection_mint,
+        collection_metadata,
+        system_program,
+        sysvar_instructions,
+        ..
+    } = params;
+
+    token_metadata_instruction(
+        &[
+            (Some(authority), false, true),
+            (delegate_record.as_ref(), false, false),
+            (Some(metadata), true, false),
+            (Some(collection_mint), false, false),
+            (Some(collection_metadata), true, false),
+            (Some(system_program), false, false),
+            (Some(sysvar_instructions), false, false),
+        ],
+        // VerificationArgs::CollectionV1
+        vec![discriminator::UNVERIFY, 1],
+    )
+}
+
+/// MetadataUnverifyCollectionParams
+pub struct MetadataUnverifyCollectionParams<'a: 'b, 'b> {
+    /// Collection update authority or delegate.
+    pub authority: AccountInfo<'a>,
+    /// authority_signer_seeds
+    pub authority_signer_seeds: Option<&'b [&'b [u8]]>,
+    /// delegate_record
+    pub delegate_record: Option<AccountInfo<'a>>,
+    /// Metadata of the collection member.
+    pub metadata: AccountInfo<'a>,
+    /// collection_mint
+    pub collection_mint: AccountInfo<'a>,
+    /// Collection metadata, which may have been burned.
+    pub collection_metadata: AccountInfo<'a>,
+    /// system_program
+    pub system_program: AccountInfo<'a>,
+    /// sysvar_instructions
+    pub sysvar_instructions: AccountInfo<'a>,
+    /// token_metadata_program
+    pub token_metadata_program: AccountInfo<'a>,
+}
+
+/// Delegate created by `mpl_token_metadata_delegate`. Token delegates carry an
+/// amount and are recorded in the token record of programmable NFTs; the
+/// others are metadata delegates with their own delegate record PDA.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DelegateArgs {
+    Collection,
+    Sale { amount: u64 },
+    Transfer { amount: u64 },
+    Data,
+    Utility { amount: u64 },
+    Staking { amount: u64 },
+    Standard { amount: u64 },
+    LockedTransfer { amount: u64, locked_address: Pubkey },
+    ProgrammableConfig,
+}
+
+impl DelegateArgs {
+    fn write(&self, data: &mut Vec<u8>) {
+        // Variant index, in `DelegateArgs` order, and the payload before authorization_data.
+        let (variant, amount, has_authorization_data) = match *self {
+            Self::Collection => (0, None, true),
+            Self::Sale { amount } => (1, Some(amount), true),
+            Self::Transfer { amount } => (2, Some(amount), true),
+            Self::Data => (3, None, true),
+            Self::Utility { amount } => (4, Some(amount), true),
+            Self::Staking { amount } => (5, Some(amount), true),
+            Self::Standard { amount } => (6, Some(amount), false),
+            Self::LockedTransfer { amount, .. } => (7, Some(amount), true),
+            Self::ProgrammableConfig => (8, None, true),
+        };
+        data.push(variant);
+        if let Some(amount) = amount {
+            data.extend_from_slice(&amount.to_le_bytes());
+        }
+        if let Self::LockedTransfer { locked_address, .. } = self {
+            data.extend_from_slice(locked_address.as_ref());
+        }
+        if has_authorization_data {
+            data.push(0);
+        }
+    }
+
+    /// Matching `RevokeArgs` variant index.
+    fn revoke_variant(&self) -> u8 {
+        match self {
+            Self::Collection => 0,
+            Self::Sale { .. } => 1,
+            Self::Transfer { .. } => 2,
+            Self::Data => 3,
+            Self::Utility { .. } => 4,
+            Self::Staking { .. } => 5,
+            Self::Standard { .. } => 6,
+            Self::LockedTransfer { .. } => 7,
+            Self::ProgrammableConfig => 8,
+        }
+    }
+}
+
+/// Approves `delegate` with `Delegate`.
+pub fn mpl_token_metadata_delegate(
+    params: MetadataDelegateParams<'_, '_>,
+    args: DelegateArgs,
+) -> ProgramResult {
+    invoke_token_metadata(
+        &params.token_metadata_program,
+        delegate_instruction(&params, args),
+        &[params.authority_signer_seeds, params.payer_signer_seeds],
+    )
+}
+
+fn delegate_instruction<'a>(
+    params: &MetadataDelegateParams<'a, '_>,
+    args: DelegateArgs,
+) -> (Instruction, Vec<AccountInfo<'a>>) {
+    let mut data = vec![discriminator::DELEGATE];
+    args.write(&mut data);
+
+    delegate_or_revoke_instruction(params, data)
+}
+
+/// Revokes the `delegate` approved for the same kind of delegate with `Revoke`.
+/// Amounts and addresses in `args` are ignored.
+pub fn mpl_token_metadata_revoke(
+    params: MetadataDelegateParams<'_, '_>,
+    args: DelegateArgs,
+) -> ProgramResult {
+    invoke_token_metadata(
+        &params.token_metadata_program,
+        revoke_instruction(&params, args),
+        &[params.authority_signer_seeds, params.payer_signer_seeds],
+    )
+}
+
+fn revoke_instruction<'a>(
+    params: &MetadataDelegateParams<'a, '_>,
+    args: DelegateArgs,
+) -> (Instruction, Vec<AccountInfo<'a>>) {
+    delegate_or_revoke_instruction(params, vec![discriminator::REVOKE, args.revoke_variant()])
+}
+
+fn delegate_or_revoke_instruction<'a>(
+    params: &MetadataDelegateParams<'a, '_>,
+    data: Vec<u8>,
+) -> (Instruction, Vec<AccountInfo<'a>>) {
+    let MetadataDelegateParams {
+        delegate_record,
+        delegate,
+        metadata,
+        master_edition,
+        token_record,
+        mint,
+        token,
+        authority,
+        payer,
+        system_program,
+        sysvar_instructions,
+        token_program,
+        authorization_rules_program,
+        authorization_rules,
+        ..
+    } = params;
+
+    token_metadata_instruction(
+        &[
+            (delegate_record.as_ref(), true, false),
+            (Some(delegate), false, false),
+            (Some(metadata), true, false),
+            (master_edition.as_ref(), false, false),
+            (token_record.as_ref(), true, false),
+            (Some(mint), false, false),
+            (token.as_ref(), true, false),
+            (Some(authority), false, true),
+            (Some(payer), true, true),
+            (Some(system_program), false, false),
+            (Some(sysvar_instructions), false, false),
+            (token_program.as_ref(), false, false),
+            (authorization_rules_program.as_ref(), false, false),
+            (authorization_rules.as_ref(), false, false),
+        ],
+        data,
+    )
+}
+
+/// MetadataDelegateParams
+pub struct MetadataDelegateParams<'a: 'b, 'b> {
+    /// Required for metadata delegates.
+    pub delegate_record: Option<AccountInfo<'a>>,
+    /// delegate
+    pub delegate: AccountInfo<'a>,
+    /// metadata
+    pub metadata: AccountInfo<'a>,
+    /// master_edition
+    pub master_edition: Option<AccountInfo<'a>>,
+    /// Required for token delegates of programmable NFTs.
+    pub token_record: Option<AccountInfo<'a>>,
+    /// mint
+    pub mint: AccountInfo<'a>,
+    /// Required for token delegates.
+    pub token: Option<AccountInfo<'a>>,
+    /// Token owner for token delegates, update authority for metadata delegates.
+    pub authority: AccountInfo<'a>,
+    /// authority_signer_seeds
+    pub authority_signer_seeds: Option<&'b [&'b [u8]]>,
+    /// payer
+    pub payer: AccountInfo<'a>,
+    /// payer_signer_seeds
+    pub payer_signer_seeds: Option<&'b [&'b [u8]]>,
+    /// system_program
+    pub system_program: AccountInfo<'a>,
+    /// sysvar_instructions
+    pub sysvar_instructions: AccountInfo<'a>,
+    /// Required for token delegates.
+    pub token_program: Option<AccountInfo<'a>>,
+    /// authorization_rules_program
+    pub authorization_rules_program: Option<AccountInfo<'a>>,
+    /// authorization_rules
+    pub authorization_rules: Option<AccountInfo<'a>>,
+    /// token_metadata_program
+    pub token_metadata_program: AccountInfo<'a>,
+}
+
+/// Locks the token with `LockV1`, signed by a utility, staking or locked
+/// transfer delegate. Non-programmable tokens are frozen instead.
+pub fn mpl_token_metadata_lock(params: MetadataLockParams<'_, '_>) -> ProgramResult {
+    invoke_token_metadata(
+        &params.token_metadata_program,
+        lock_instruction(&params),
+        &[params.authority_signer_seeds, params.payer_signer_seeds],
+    )
+}
+
+fn lock_instruction<'a>(
+    params: &MetadataLockParams<'a, '_>,
+) -> (Instruction, Vec<AccountInfo<'a>>) {
+    // LockArgs::V1 without authorization_data.
+    lock_or_unlock_instruction(params, vec![discriminator::LOCK, 0, 0])
+}
+
+/// Reverts `mpl_token_metadata_lock` with `UnlockV1`.
+pub fn mpl_token_metadata_unlock(params: MetadataLockParams<'_, '_>) -> ProgramResult {
+    invoke_token_metadata(
+        &params.token_metadata_program,
+        unlock_instruction(&params),
+        &[params.authority_signer_seeds, params.payer_signer_seeds],
+    )
+}
+
+fn unlock_instruction<'a>(
+    params: &MetadataLockParams<'a, '_>,
+) -> (Instruction, Vec<AccountInfo<'a>>) {
+    lock_or_unlock_instruction(params, vec![discriminator::UNLOCK, 0, 0])
+}
+
+fn lock_or_unlock_instruction<'a>(
+    params: &MetadataLockParams<'a, '_>,
+    data: Vec<u8>,
+) -> (Instruction, Vec<AccountInfo<'a>>) {
+    let MetadataLockParams {
+        authority,
+        token_owner,
+        token,
+        mint,
+        metadata,
+        edition,
+        token_record,
+        payer,
+        system_program,
+        sysvar_instructions,
+        token_program,
+        authorization_rules_program,
+        authorization_rules,
+        ..
+    } = params;
+
+    token_metadata_instruction(
+        &[
+            (Some(authority), false, true),
+            (token_owner.as_ref(), false, false),
+            (Some(token), true, false),
+            (Some(mint), false, false),
+            (Some(metadata), true, false),
+            (edition.as_ref(), false, false),
+            (token_record.as_ref(), true, false),
+            (Some(payer), true, true),
+            (Some(system_program), false, false),
+            (Some(sysvar_instructions), false, false),
+            (token_program.as_ref(), false, false),
+            (authorization_rules_program.as_ref(), false, false),
+            (authorization_rules.as_ref(), false, false),
+        ],
+        data,
+    )
+}
+
+/// MetadataLockParams
+pub struct MetadataLockParams<'a: 'b, 'b> {
+    /// Token delegate.
+    pub authority: AccountInfo<'a>,
+    /// authority_signer_seeds
+    pub authority_signer_seeds: Option<&'b [&'b [u8]]>,
+    /// token_owner
+    pub token_owner: Option<AccountInfo<'a>>,
+    /// token
+    pub token: AccountInfo<'a>,
+    /// mint
+    pub mint: AccountInfo<'a>,
+    /// metadata
+    pub metadata: AccountInfo<'a>,
+    /// edition
+    pub edition: Option<AccountInfo<'a>>,
+    /// Required for programmable NFTs.
+    pub token_record: Option<AccountInfo<'a>>,
+    /// payer
+    pub payer: AccountInfo<'a>,
+    /// payer_signer_seeds
+    pub payer_signer_seeds: Option<&'b [&'b [u8]]>,
+    /// system_program
+    pub system_program: AccountInfo<'a>,
+    /// sysvar_instructions
+    pub sysvar_instructions: AccountInfo<'a>,
+    /// token_program
+    pub token_program: Option<AccountInfo<'a>>,
+    /// authorization_rules_program
+    pub authorization_rules_program: Option<AccountInfo<'a>>,
+    /// authorization_rules
+    pub authorization_rules: Option<AccountInfo<'a>>,
+    /// token_metadata_program
+    pub token_metadata_program: AccountInfo<'a>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(n: u8) -> Pubkey {
+        Pubkey::new_from_array([n; 32])
+    }
+
+    /// Builds one account per key, plus the Token Metadata program account.
+    fn accounts<'a>(
+        keys: &'a [Pubkey],
+        lamports: &'a mut [u64],
+    ) -> (Vec<AccountInfo<'a>>, AccountInfo<'a>) {
+        let (program_lamports, lamports) = lamports.split_last_mut().unwrap();
+        let infos = keys
+            .iter()
+            .zip(lamports.iter_mut())
+            .map(|(key, lamports)| {
+                AccountInfo::new(
+                    key,
+                    false,
+                    false,
+                    lamports,
+                    &mut [],
+                    &TOKEN_METADATA_PROGRAM_ID,
+                    false,
+                )
+            })
+            .collect();
+        let program = AccountInfo::new(
+            &TOKEN_METADATA_PROGRAM_ID,
+            false,
+            false,
+            program_lamports,
+            &mut [],
+            &TOKEN_METADATA_PROGRAM_ID,
+            true,
+        );
+        (infos, program)
+    }
+
+    /// Expected metas as `(n, is_writable, is_signer)`, `n` being the account
+    /// with `key(n)` or 0 for a missing optional account.
+    fn metas(accounts: &[(u8, bool, bool)]) -> Vec<AccountMeta> {
+        accounts
+            .iter()
+            .map(|&(n, is_writable, is_signer)| AccountMeta {
+                pubkey: if n == 0 {
+                    TOKEN_METADATA_PROGRAM_ID
+                } else {
+                    key(n)
+                },
+                is_signer,
+                is_writable,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn create_v1_data_and_accounts() {
+        let keys: Vec<Pubkey> = (1..=9).map(key).collect();
+        let mut lamports = [0; 10];
+        let (infos, program) = accounts(&keys, &mut lamports);
+        let account = |n: usize| infos[n - 1].clone();
+        let creators = [Creator {
+            address: key(30),
+            verified: true,
+            share: 100,
+        }];
+        let mint_signer_seeds: &[&[u8]] = &[b"mint"];
+
+        let (instruction, _) = create_v1_instruction(&MetadataCreateV1Params {
+            metadata: account(1),
+            master_edition: Some(account(2)),
+            mint: account(3),
+            mint_signer_seeds: Some(mint_signer_seeds),
+            authority: account(4),
+            authority_signer_seeds: None,
+            payer: account(5),
+            payer_signer_seeds: None,
+            update_authority: account(6),
+            asset_data: AssetData {
+                name: "Name",
+                symbol: "SYM",
+                uri: "https://x",
+                seller_fee_basis_points: 500,
+                creators: Some(&creators),
+                primary_sale_happened: false,
+                is_mutable: true,
+                token_standard: TokenStandard::ProgrammableNonFungible,
+                collection: Some(Collection {
+                    verified: false,
+                    key: key(31),
+                }),
+                collection_details: Some(CollectionDetails::V1 { size: 5 }),
+                rule_set: Some(key(32)),
+            },
+            decimals: Some(0),
+            print_supply: Some(PrintSupply::Limited(10)),
+            system_program: account(7),
+            sysvar_instructions: account(8),
+            token_program: account(9),
+            token_metadata_program: program.clone(),
+        });
+        assert_eq!(instruction.program_id, TOKEN_METADATA_PROGRAM_ID);
+        assert_eq!(
+            instruction.data,
+            [
+                &[42, 0][..],
+                &[4, 0, 0, 0],
+                b"Name",
+                &[3, 0, 0, 0],
+                b"SYM",
+                &[9, 0, 0, 0],
+                b"https://x",
+                &[244, 1],
+                &[1, 1, 0, 0, 0],
+                &[30; 32],
+                &[1, 100],
+                &[0, 1, 4],
+                &[1, 0],
+                &[31; 32],
+                &[0],
+                &[1, 0, 5, 0, 0, 0, 0, 0, 0, 0],
+                &[1],
+                &[32; 32],
+                &[1, 0],
+                &[1, 1, 10, 0, 0, 0, 0, 0, 0, 0],
+            ]
+            .concat()
+        );
+        assert_eq!(
+            instruction.accounts,
+            metas(&[
+                (1, true, false),
+                (2, true, false),
+                (3, true, true),
+                (4, false, true),
+                (5, true, true),
+                (6, false, false),
+                (7, false, false),
+                (8, false, false),
+                (9, false, false),
+            ])
+        );
+
+        let (instruction, _) = create_v1_instruction(&MetadataCreateV1Params {
+            metadata: account(1),
+            master_edition: None,
+            mint: account(3),
+            mint_signer_seeds: None,
+            authority: account(4),
+            authority_signer_seeds: None,
+            payer: account(5),
+            payer_signer_seeds: None,
+            update_authority: account(6),
+            asset_data: AssetData {
+                name: "",
+                symbol: "",
+                uri: "",
+                seller_fee_basis_points: 0,
+                creators: None,
+                primary_sale_happened: true,
+                is_mutable: false,
+                token_standard: TokenStandard::Fungible,
+                collection: None,
+                collection_details: Some(CollectionDetails::V2),
+                rule_set: None,
+            },
+            decimals: None,
+            print_supply: Some(PrintSupply::Unlimited),
+            system_program: account(7),
+            sysvar_instructions: account(8),
+            token_program: account(9),
+            token_metadata_program: program.clone(),
+        });
+        assert_eq!(
+            instruction.data,
+            [
+                &[42, 0][..],
+                &[0; 12],
+                &[0, 0],
+                &[0, 1, 0, 2, 0, 0],
+                &[1, 1, 0, 0, 0, 0, 0, 0, 0, 0],
+                &[0, 0],
+                &[1, 2],
+            ]
+            .concat()
+        );
+        assert_eq!(
+            instruction.accounts,
+            metas(&[
+                (1, true, false),
+                (0, false, false),
+                (3, true, false),
+                (4, false, true),
+                (5, true, true),
+                (6, false, false),
+                (7, false, false),
+                (8, false, false),
+                (9, false, false),
+            ])
+        );
+    }
+
+    #[test]
+    fn mint_v1_data_and_accounts() {
+        let keys: Vec<Pubkey> = (1..=15).map(key).collect();
+        let mut lamports = [0; 16];
+        let (infos, program) = accounts(&keys, &mut lamports);
+        let account = |n: usize| infos[n - 1].clone();
+        let mint_params = |optional: bool| MetadataMintV1Params {
+            token: account(1),
+            token_owner: optional.then(|| account(2)),
+            metadata: account(3),
+            master_edition: optional.then(|| account(4)),
+            token_record: optional.then(|| account(5)),
+            mint: account(6),
+            authority: account(7),
+            authority_signer_seeds: None,
+            delegate_record: optional.then(|| account(8)),
+            payer: account(9),
+            payer_signer_seeds: None,
+            amount: 1_000_000,
+            system_program: account(10),
+            sysvar_instructions: account(11),
+            token_program: account(12),
+            associated_token_program: account(13),
+            authorization_rules_program: optional.then(|| account(14)),
+            authorization_rules: optional.then(|| account(15)),
+            token_metadata_program: program.clone(),
+        };
+        let data = [43, 0, 0x40, 0x42, 0x0f, 0, 0, 0, 0, 0, 0];
+
+        let (instruction, _) = mint_v1_instruction(&mint_params(true));
+        assert_eq!(instruction.data, data);
+        assert_eq!(
+            instruction.accounts,
+            metas(&[
+                (1, true, false),
+                (2, false, false),
+                (3, false, false),
+                (4, false, false),
+                (5, true, false),
+                (6, true, false),
+                (7, false, true),
+                (8, false, false),
+                (9, true, true),
+                (10, false, false),
+                (11, false, false),
+                (12, false, false),
+                (13, false, false),
+                (14, false, false),
+                (15, false, false),
+            ])
+        );
+
+        let (instruction, _) = mint_v1_instruction(&mint_params(false));
+        assert_eq!(instruction.data, data);
+        assert_eq!(
+            instruction.accounts,
+            metas(&[
+                (1, true, false),
+                (0, false, false),
+                (3, false, false),
+                (0, false, false),
+                (0, false, false),
+                (6, true, false),
+                (7, false, true),
+                (0, false, false),
+                (9, true, true),
+                (10, false, false),
+                (11, false, false),
+                (12, false, false),
+                (13, false, false),
+                (0, false, false),
+                (0, false, false),
+            ])
+        );
+    }
+
+    #[test]
+    fn transfer_v1_data_and_accounts() {
+        let keys: Vec<Pubkey> = (1..=17).map(key).collect();
+        let mut lamports = [0; 18];
+        let (infos, program) = accounts(&keys, &mut lamports);
+        let account = |n: usize| infos[n - 1].clone();
+        let transfer_params = |optional: bool| MetadataTransferV1Params {
+            token: account(1),
+            token_owner: account(2),
+            destination_token: account(3),
+            destination_owner: account(4),
+            mint: account(5),
+            metadata: account(6),
+            edition: optional.then(|| account(7)),
+            owner_token_record: optional.then(|| account(8)),
+            destination_token_record: optional.then(|| account(9)),
+            authority: account(10),
+            authority_signer_seeds: None,
+            payer: account(11),
+            payer_signer_seeds: None,
+            amount: 1,
+            system_program: account(12),
+            sysvar_instructions: account(13),
+            token_program: account(14),
+            associated_token_program: account(15),
+            authorization_rules_program: optional.then(|| account(16)),
+            authorization_rules: optional.then(|| account(17)),
+            token_metadata_program: program.clone(),
+        };
+        let data = [49, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0];
+
+        let (instruction, _) = transfer_v1_instruction(&transfer_params(true));
+        assert_eq!(instruction.data, data);
+        assert_eq!(
+            instruction.accounts,
+            metas(&[
+                (1, true, false),
+                (2, false, false),
+                (3, true, false),
+                (4, false, false),
+                (5, false, false),
+                (6, true, false),
+                (7, false, false),
+                (8, true, false),
+                (9, true, false),
+                (10, false, true),
+                (11, true, true),
+                (12, false, false),
+                (13, false, false),
+                (14, false, false),
+                (15, false, false),
+                (16, false, false),
+                (17, false, false),
+            ])
+        );
+
+        let (instruction, _) = transfer_v1_instruction(&transfer_params(false));
+        assert_eq!(instruction.data, data);
+        assert_eq!(
+            instruction.accounts,
+            metas(&[
+                (1, true, false),
+                (2, false, false),
+                (3, true, false),
+                (4, false, false),
+                (5, false, false),
+                (6, true, false),
+                (0, false, false),
+                (0, false, false),
+                (0, false, false),
+                (10, false, true),
+                (11, true, true),
+                (12, false, false),
+                (13, false, false),
+                (14, false, false),
+                (15, false, false),
+                (0, false, false),
+                (0, false, false),
+            ])
+        );
+    }
+
+    #[test]
+    fn delegate_and_revoke_data_and_accounts() {
+        let keys: Vec<Pubkey> = (1..=16).map(key).collect();
+        let mut lamports = [0; 17];
+        let (infos, program) = accounts(&keys, &mut lamports);
+        let account = |n: usize| infos[n - 1].clone();
+        let delegate_params = |optional: bool| MetadataDelegateParams {
+            delegate_record: optional.then(|| account(1)),
+            delegate: account(2),
+            metadata: account(3),
+            master_edition: optional.then(|| account(4)),
+            token_record: optional.then(|| account(5)),
+            mint: account(6),
+            token: optional.then(|| account(7)),
+            authority: account(8),
+            authority_signer_seeds: None,
+            payer: account(9),
+            payer_signer_seeds: None,
+            system_program: account(10),
+            sysvar_instructions: account(11),
+            token_program: optional.then(|| account(12)),
+            authorization_rules_program: optional.then(|| account(13)),
+            authorization_rules: optional.then(|| account(14)),
+            token_metadata_program: program.clone(),
+        };
+
+        let (instruction, _) =
+            delegate_instruction(&delegate_params(true), DelegateArgs::Sale { amount: 1 });
+        assert_eq!(instruction.data, [44, 1, 1, 0, 0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(
+            instruction.accounts,
+            metas(&[
+                (1, true, false),
+                (2, false, false),
+                (3, true, false),
+                (4, false, false),
+                (5, true, false),
+                (6, false, false),
+                (7, true, false),
+                (8, false, true),
+                (9, true, true),
+                (10, false, false),
+                (11, false, false),
+                (12, false, false),
+                (13, false, false),
+                (14, false, false),
+            ])
+        );
+
+        let (instruction, _) =
+            delegate_instruction(&delegate_params(false), DelegateArgs::Collection);
+        assert_eq!(instruction.data, [44, 0, 0]);
+        assert_eq!(
+            instruction.accounts,
+            metas(&[
+                (0, false, false),
+                (2, false, false),
+                (3, true, false),
+                (0, false, false),
+                (0, false, false),
+                (6, false, false),
+                (0, false, false),
+                (8, false, true),
+                (9, true, true),
+                (10, false, false),
+                (11, false, false),
+                (0, false, false),
+                (0, false, false),
+                (0, false, false),
+            ])
+        );
+
+        assert_eq!(
+            delegate_instruction(&delegate_params(true), DelegateArgs::Standard { amount: 2 })
+                .0
+                .data,
+            [44, 6, 2, 0, 0, 0, 0, 0, 0, 0]
+        );
+
+        assert_eq!(
+            delegate_instruction(
+                &delegate_params(true),
+                DelegateArgs::LockedTransfer {
+                    amount: 3,
+                    locked_address: key(40),
+                }
+            )
+            .0
+            .data,
+            [&[44, 7, 3, 0, 0, 0, 0, 0, 0, 0][..], &[40; 32], &[0]].concat()
+        );
+
+        assert_eq!(
+            revoke_instruction(
+                &delegate_params(true),
+                DelegateArgs::LockedTransfer {
+                    amount: 3,
+                    locked_address: key(40),
+                }
+            )
+            .0
+            .data,
+            [45, 7]
+        );
+    }
+
+    #[test]
+    fn verify_collection_data_and_accounts() {
+        let keys: Vec<Pubkey> = (1..=8).map(key).collect();
+        let mut lamports = [0; 9];
+        let (infos, program) = accounts(&keys, &mut lamports);
+        let account = |n: usize| infos[n - 1].clone();
+        let verify_params = |optional: bool| MetadataVerifyCollectionParams {
+            authority: account(1),
+            authority_signer_seeds: None,
+            delegate_record: optional.then(|| account(2)),
+            metadata: account(3),
+            collection_mint: account(4),
+            collection_metadata: account(5),
+            collection_master_edition: account(6),
+            system_program: account(7),
+            sysvar_instructions: account(8),
+            token_metadata_program: program.clone(),
+        };
+
+        let (instruction, _) = verify_collection_instruction(&verify_params(true));
+        assert_eq!(instruction.data, [52, 1]);
+        assert_eq!(
+            instruction.accounts,
+            metas(&[
+                (1, false, true),
+                (2, false, false),
+                (3, true, false),
+                (4, false, false),
+                (5, true, false),
+                (6, false, false),
+                (7, false, false),
+                (8, false, false),
+            ])
+        );
+
+        assert_eq!(
+            verify_collection_instruction(&verify_params(false))
+                .0
+                .accounts[1],
+            metas(&[(0, false, false)])[0]
+        );
+
+        let mut wrong_program = verify_params(false);
+        wrong_program.token_metadata_program = account(8);
+        assert_eq!(
+            mpl_token_metadata_verify_collection(wrong_program),
+            Err(ProgramError::IncorrectProgramId)
+        );
+    }
+
+    #[test]
+    fn update_v1_data_and_accounts() {
+        let keys: Vec<Pubkey> = (1..=11).map(key).collect();
+        let mut lamports = [0; 12];
+        let (infos, program) = accounts(&keys, &mut lamports);
+        let account = |n: usize| infos[n - 1].clone();
+        let update_params = |optional: bool| MetadataUpdateV1Params {
+            authority: account(1),
+            authority_signer_seeds: None,
+            delegate_record: optional.then(|| account(2)),
+            token: optional.then(|| account(3)),
+            mint: account(4),
+            metadata: account(5),
+            edition: optional.then(|| account(6)),
+            payer: account(7),
+            payer_signer_seeds: None,
+            new_update_authority: None,
+            data: None,
+            primary_sale_happened: None,
+            is_mutable: None,
+            system_program: account(8),
+            sysvar_instructions: account(9),
+            authorization_rules_program: optional.then(|| account(10)),
+            authorization_rules: optional.then(|| account(11)),
+            token_metadata_program: program.clone(),
+        };
+
+        let creators = [Creator {
+            address: key(30),
+            verified: false,
+            share: 100,
+        }];
+        let (instruction, _) = update_v1_instruction(&MetadataUpdateV1Params {
+            new_update_authority: Some(key(50)),
+            data: Some(UpdateData {
+                name: "A",
+                symbol: "",
+                uri: "u",
+                seller_fee_basis_points: 1000,
+                creators: Some(&creators),
+            }),
+            primary_sale_happened: Some(true),
+            is_mutable: Some(false),
+            ..update_params(true)
+        });
+        assert_eq!(
+            instruction.data,
+            [
+                &[50, 0, 1][..],
+                &[50; 32],
+                &[1],
+                &[1, 0, 0, 0],
+                b"A",
+                &[0, 0, 0, 0],
+                &[1, 0, 0, 0],
+                b"u",
+                &[232, 3],
+                &[1, 1, 0, 0, 0],
+                &[30; 32],
+                &[0, 100],
+                &[1, 1, 1, 0],
+                &[0, 0, 0, 0, 0],
+            ]
+            .concat()
+        );
+        assert_eq!(
+            instruction.accounts,
+            metas(&[
+                (1, false, true),
+                (2, false, false),
+                (3, false, false),
+                (4, false, false),
+                (5, true, false),
+                (6, false, false),
+                (7, true, true),
+                (8, false, false),
+                (9, false, false),
+                (10, false, false),
+                (11, false, false),
+            ])
+        );
+
+        let (instruction, _) = update_v1_instruction(&update_params(false));
+        assert_eq!(instruction.data, [50, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(
+            instruction.accounts,
+            metas(&[
+                (1, false, true),
+                (0, false, false),
+                (0, false, false),
+                (4, false, false),
+                (5, true, false),
+                (0, false, false),
+                (7, true, true),
+                (8, false, false),
+                (9, false, false),
+                (0, false, false),
+                (0, false, false),
+            ])
+        );
+    }
+
+    #[test]
+    fn burn_v1_data_and_accounts() {
+        let keys: Vec<Pubkey> = (1..=14).map(key).collect();
+        let mut lamports = [0; 15];
+        let (infos, program) = accounts(&keys, &mut lamports);
+        let account = |n: usize| infos[n - 1].clone();
+        let burn_params = |optional: bool| MetadataBurnV1Params {
+            authority: account(1),
+            authority_signer_seeds: None,
+            collection_metadata: optional.then(|| account(2)),
+            metadata: account(3),
+            edition: optional.then(|| account(4)),
+            mint: account(5),
+            token: account(6),
+            master_edition: optional.then(|| account(7)),
+            master_edition_mint: optional.then(|| account(8)),
+            master_edition_token: optional.then(|| account(9)),
+            edition_marker: optional.then(|| account(10)),
+            token_record: optional.then(|| account(11)),
+            amount: 258,
+            system_program: account(12),
+            sysvar_instructions: account(13),
+            token_program: account(14),
+            token_metadata_program: program.clone(),
+        };
+        let data = [41, 0, 2, 1, 0, 0, 0, 0, 0, 0];
+
+        let (instruction, _) = burn_v1_instruction(&burn_params(true));
+        assert_eq!(instruction.data, data);
+        assert_eq!(
+            instruction.accounts,
+            metas(&[
+                (1, true, true),
+                (2, true, false),
+                (3, true, false),
+                (4, true, false),
+                (5, true, false),
+                (6, true, false),
+                (7, true, false),
+                (8, false, false),
+                (9, false, false),
+                (10, true, false),
+                (11, true, false),
+                (12, false, false),
+                (13, false, false),
+                (14, false, false),
+            ])
+        );
+
+        let (instruction, _) = burn_v1_instruction(&burn_params(false));
+        assert_eq!(instruction.data, data);
+        assert_eq!(
+            instruction.accounts,
+            metas(&[
+                (1, true, true),
+                (0, false, false),
+                (3, true, false),
+                (0, false, false),
+                (5, true, false),
+                (6, true, false),
+                (0, false, false),
+                (0, false, false),
+                (0, false, false),
+                (0, false, false),
+                (0, false, false),
+                (12, false, false),
+                (13, false, false),
+                (14, false, false),
+            ])
+        );
+    }
+
+    #[test]
+    fn unverify_collection_data_and_accounts() {
+        let keys: Vec<Pubkey> = (1..=7).map(key).collect();
+        let mut lamports = [0; 8];
+        let (infos, program) = accounts(&keys, &mut lamports);
+        let account = |n: usize| infos[n - 1].clone();
+        let unverify_params = |optional: bool| MetadataUnverifyCollectionParams {
+            authority: account(1),
+            authority_signer_seeds: None,
+            delegate_record: optional.then(|| account(2)),
+            metadata: account(3),
+            collection_mint: account(4),
+            collection_metadata: account(5),
+            system_program: account(6),
+            sysvar_instructions: account(7),
+            token_metadata_program: program.clone(),
+        };
+
+        let (instruction, _) = unverify_collection_instruction(&unverify_params(true));
+        assert_eq!(instruction.data, [53, 1]);
+        assert_eq!(
+            instruction.accounts,
+            metas(&[
+                (1, false, true),
+                (2, false, false),
+                (3, true, false),
+                (4, false, false),
+                (5, true, false),
+                (6, false, false),
+                (7, false, false),
+            ])
+        );
+
+        let (instruction, _) = unverify_collection_instruction(&unverify_params(false));
+        assert_eq!(instruction.data, [53, 1]);
+        assert_eq!(instruction.accounts[1], metas(&[(0, false, false)])[0]);
+    }
+
+    #[test]
+    fn lock_and_unlock_data_and_accounts() {
+        let keys: Vec<Pubkey> = (1..=13).map(key).collect();
+        let mut lamports = [0; 14];
+        let (infos, program) = accounts(&keys, &mut lamports);
+        let account = |n: usize| infos[n - 1].clone();
+        let lock_params = |optional: bool| MetadataLockParams {
+            authority: account(1),
+            authority_signer_seeds: None,
+            token_owner: optional.then(|| account(2)),
+            token: account(3),
+            mint: account(4),
+            metadata: account(5),
+            edition: optional.then(|| account(6)),
+            token_record: optional.then(|| account(7)),
+            payer: account(8),
+            payer_signer_seeds: None,
+            system_program: account(9),
+            sysvar_instructions: account(10),
+            token_program: optional.then(|| account(11)),
+            authorization_rules_program: optional.then(|| account(12)),
+            authorization_rules: optional.then(|| account(13)),
+            token_metadata_program: program.clone(),
+        };
+
+        let (instruction, _) = lock_instruction(&lock_params(true));
+        assert_eq!(instruction.data, [46, 0, 0]);
+        assert_eq!(
+            instruction.accounts,
+            metas(&[
+                (1, false, true),
+                (2, false, false),
+                (3, true, false),
+                (4, false, false),
+                (5, true, false),
+                (6, false, false),
+                (7, true, false),
+                (8, true, true),
+                (9, false, false),
+                (10, false, false),
+                (11, false, false),
+                (12, false, false),
+                (13, false, false),
+            ])
+        );
+
+        let (instruction, _) = unlock_instruction(&lock_params(false));
+        assert_eq!(instruction.data, [47, 0, 0]);
+        assert_eq!(
+            instruction.accounts,
+            metas(&[
+                (1, false, true),
+                (0, false, false),
+                (3, true, false),
+                (4, false, false),
+                (5, true, false),
+                (0, false, false),
+                (0, false, false),
+                (8, true, true),
+                (9, false, false),
+                (10, false, false),
+                (0, false, false),
+                (0, false, false),
+                (0, false, false),
+            ])
+        );
+    }
+}
@@ -0,0 +1,212 @@
+use solana_program::{account_info::AccountInfo, msg, program_error::ProgramError, pubkey::Pubkey};
+
+use super::{assert_token_metadata_account, Key, PREFIX, TOKEN_METADATA_PROGRAM_ID};
+use crate::borsh_reader::BorshReader;
+
+/// Seed shared by edition and edition marker PDAs.
+pub const EDITION: &str = "edition";
+
+/// Last seed of the `EditionMarkerV2` PDA used by programmable editions.
+pub const EDITION_MARKER_V2: &str = "marker";
+
+/// Number of editions tracked by each `EditionMarker` account.
+pub const EDITION_MARKER_BIT_SIZE: u64 = 248;
+
+/// Derives the edition PDA of a mint. Master editions and print editions share
+/// the same seeds, so this is both the master edition of an original and the
+/// edition account of a print.
+pub fn find_edition_address(mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PREFIX.as_bytes(),
+            TOKEN_METADATA_PROGRAM_ID.as_ref(),
+            mint.as_ref(),
+            EDITION.as_bytes(),
+        ],
+        &TOKEN_METADATA_PROGRAM_ID,
+    )
+}
+
+/// Derives the `EditionMarker` PDA tracking `edition` of the master edition of
+/// `master_mint`. The last seed is the marker number as a decimal string.
+pub fn find_edition_marker_address(master_mint: &Pubkey, edition: u64) -> (Pubkey, u8) {
+    let marker_number = (edition / EDITION_MARKER_BIT_SIZE).to_string();
+    Pubkey::find_program_address(
+        &[
+            PREFIX.as_bytes(),
+            TOKEN_METADATA_PROGRAM_ID.as_ref(),
+            master_mint.as_ref(),
+            EDITION.as_bytes(),
+            marker_number.as_bytes(),
+        ],
+        &TOKEN_METADATA_PROGRAM_ID,
+    )
+}
+
+/// Derives the single `EditionMarkerV2` PDA of a programmable master edition.
+pub fn find_edition_marker_v2_address(master_mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PREFIX.as_bytes(),
+            TOKEN_METADATA_PROGRAM_ID.as_ref(),
+            master_mint.as_ref(),
+            EDITION.as_bytes(),
+            EDITION_MARKER_V2.as_bytes(),
+        ],
+        &TOKEN_METADATA_PROGRAM_ID,
+    )
+}
+
+/// Returns the ledger byte index and bit mask of an edition in its marker. For
+/// `EditionMarker` accounts pass the edition number, the position within the
+/// marker is taken modulo `EDITION_MARKER_BIT_SIZE`; for `EditionMarkerV2` use
+/// `get_edition_marker_v2_bit`.
+pub fn get_edition_marker_bit(edition: u64) -> (usize, u8) {
+    get_edition_marker_v2_bit(edition % EDITION_MARKER_BIT_SIZE)
+}
+
+/// Same as `get_edition_marker_bit`, for the unbounded `EditionMarkerV2` ledger.
+pub fn get_edition_marker_v2_bit(edition: u64) -> (usize, u8) {
+    // Bits are filled from the most significant one.
+    ((edition / 8) as usize, 1 << (7 - edition % 8))
+}
+
+/// Reads the supply and max_supply of a `MasterEditionV1` or `MasterEditionV2`
+/// account. A `None` max_supply means unlimited prints.
+pub fn get_master_edition_supply(
+    master_edition_info: &AccountInfo,
+) -> Result<(u64, Option<u64>), ProgramError> {
+    let data = master_edition_info.try_borrow_data()?;
+    let mut reader = BorshReader { data: &data };
+    match Key::try_from(reader.read_u8()?)? {
+        Key::MasterEditionV1 | Key::MasterEditionV2 => (),
+        _ => return Err(ProgramError::InvalidAccountData),
+    }
+    let supply = reader.read_u64()?;
+    let max_supply = match reader.read_option()? {
+        true => Some(reader.read_u64()?),
+        false => None,
+    };
+
+    Ok((supply, max_supply))
+}
+
+/// Returns true if `edition` of the master edition of `master_mint` has already
+/// been printed, according to the given `EditionMarker` or `EditionMarkerV2`
+/// account. The marker's owner and PDA derivation are checked; a marker account
+/// that doesn't exist yet means nothing in its range was printed.
+pub fn is_edition_printed(
+    edition_marker_info: &AccountInfo,
+    master_mint: &Pubkey,
+    edition: u64,
+) -> Result<bool, ProgramError> {
+    // Each derivation is computed once: the v1 PDA is checked first, then v2.
+    let is_v1 = *edition_marker_info.key == find_edition_marker_address(master_mint, edition).0;
+    if !is_v1 && *edition_marker_info.key != find_edition_marker_v2_address(master_mint).0 {
+        msg!(
+            "Edition marker {} has invalid seeds",
+            edition_marker_info.key
+        );
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    if edition_marker_info.data_is_empty() {
+        return Ok(false);
+    }
+    assert_token_metadata_account(
+        edition_marker_info,
+        if is_v1 {
+            Key::EditionMarker
+        } else {
+            Key::EditionMarkerV2
+        },
+    )?;
+
+    let data = edition_marker_info.try_borrow_data()?;
+    let (ledger, (index, mask)) = if is_v1 {
+        (
+            data.get(1..32).ok_or(ProgramError::InvalidAccountData)?,
+            get_edition_marker_bit(edition),
+        )
+    } else {
+        let mut reader = BorshReader { data: &data[1..] };
+        let len = reader.read_u32()? as usize;
+        (reader.read_bytes(len)?, get_edition_marker_v2_bit(edition))
+    };
+
+    Ok(ledger.get(index).is_some_and(|byte| byte & mask != 0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn edition_marker_bits() {
+        assert_eq!(get_edition_marker_bit(0), (0, 0b1000_0000));
+        assert_eq!(get_edition_marker_bit(7), (0, 0b0000_0001));
+        assert_eq!(get_edition_marker_bit(9), (1, 0b0100_0000));
+        assert_eq!(get_edition_marker_bit(247), (30, 0b0000_0001));
+        assert_eq!(get_edition_marker_bit(248), (0, 0b1000_0000));
+        assert_eq!(get_edition_marker_v2_bit(248), (31, 0b1000_0000));
+    }
+
+    #[test]
+    fn printed_editions() {
+        let master_mint = Pubkey::new_unique();
+        let edition = EDITION_MARKER_BIT_SIZE + 9;
+        let (marker_key, _) = find_edition_marker_address(&master_mint, edition);
+        let mut data = vec![0; 32];
+        data[0] = Key::EditionMarker as u8;
+        data[2] = 0b0100_0000;
+        let mut lamports = 0;
+        let marker_info = AccountInfo::new(
+            &marker_key,
+            false,
+            false,
+            &mut lamports,
+            &mut data,
+            &TOKEN_METADATA_PROGRAM_ID,
+            false,
+        );
+
+        assert_eq!(
+            is_edition_printed(&marker_info, &master_mint, edition),
+            Ok(true)
+        );
+        assert_eq!(
+            is_edition_printed(&marker_info, &master_mint, edition + 1),
+            Ok(false)
+        );
+        assert_eq!(
+            is_edition_printed(&marker_info, &master_mint, 9),
+            Err(ProgramError::InvalidSeeds)
+        );
+
+        let (marker_v2_key, _) = find_edition_marker_v2_address(&master_mint);
+        let mut data = vec![Key::EditionMarkerV2 as u8, 2, 0, 0, 0, 0, 0b0010_0000];
+        let mut lamports = 0;
+        let marker_v2_info = AccountInfo::new(
+            &marker_v2_key,
+            false,
+            false,
+            &mut lamports,
+            &mut data,
+            &TOKEN_METADATA_PROGRAM_ID,
+            false,
+        );
+
+        assert_eq!(
+            is_edition_printed(&marker_v2_info, &master_mint, 10),
+            Ok(true)
+        );
+        assert_eq!(
+            is_edition_printed(&marker_v2_info, &master_mint, 11),
+            Ok(false)
+        );
+        assert_eq!(
+            is_edition_printed(&marker_v2_info, &master_mint, 1_000),
+            Ok(false)
+        );
+    }
+}
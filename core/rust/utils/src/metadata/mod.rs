@@ -0,0 +1,17 @@
+pub use assertions::*;
+pub use cpi::*;
+pub use edition::*;
+#[cfg(feature = "spl-token")]
+pub use royalty::*;
+pub use state::*;
+pub use token_record::*;
+pub use utils::*;
+
+mod assertions;
+mod cpi;
+mod edition;
+#[cfg(feature = "spl-token")]
+mod royalty;
+mod state;
+mod token_record;
+mod utils;
@@ -0,0 +1,250 @@
+use solana_program::{
+    account_info::AccountInfo, msg, program::invoke_signed, program_error::ProgramError,
+};
+use solana_system_interface::instruction as system_instruction;
+
+use super::{assert_metadata_account, MetadataRef};
+use crate::{
+    cmp_pubkeys,
+    fees::{checked_basis_points, checked_mul_div, Payout, PayoutPlan, Rounding},
+    token::{transfer_to_wallet, TransferToWalletParams},
+};
+
+/// Accounts to pay the royalties in tokens rather than SOL.
+#[derive(Debug)]
+pub struct RoyaltyTokenPayment<'a: 'b, 'b> {
+    /// mint
+    pub mint: AccountInfo<'a>,
+    /// Token account the royalties are paid from.
+    pub source: AccountInfo<'a>,
+    /// authority
+    pub authority: AccountInfo<'a>,
+    /// authority_signer_seeds
+    pub authority_signer_seeds: Option<&'b [&'b [u8]]>,
+    /// token_program
+    pub token_program: AccountInfo<'a>,
+    /// associated_token_program
+    pub associated_token_program: AccountInfo<'a>,
+}
+
+/// Plans the creator payouts of a sale of `price` the way Auction House does:
+/// the royalty is `seller_fee_basis_points` of the price rounded down, and each
+/// creator, verified or not, gets `royalty * share / 100` rounded down. The
+/// seller gets everything else, including the rounding dust of the split.
+pub fn plan_creator_royalties(metadata: &MetadataRef, price: u64) -> Option<PayoutPlan> {
+    let royalty = checked_basis_points(price, metadata.seller_fee_basis_points, Rounding::Down)?;
+    let payouts = metadata
+        .creators()
+        .map(|creator| {
+            Some(Payout {
+                recipient: creator.address,
+                amount: checked_mul_div(royalty, creator.share as u64, 100, Rounding::Down)?,
+            })
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    let mut plan = PayoutPlan {
+        payouts,
+        remainder: 0,
+    };
+    plan.remainder = price.checked_sub(plan.total_paid())?;
+
+    Some(plan)
+}
+
+/// Pays the creators of the metadata of `mint` their share of the royalties on
+/// a sale of `price`, and returns the amount left for the seller. Royalties are
+/// paid in lamports from the payer unless `token_payment` is given. See
+/// `plan_creator_royalties` for how the amounts are computed.
+///
+/// The creator accounts come in metadata order: the creator wallet for SOL, or
+/// the creator wallet followed by its associated token account, created if
+/// needed, for tokens.
+pub fn pay_creator_royalties<'a>(
+    params: PayCreatorRoyaltiesParams<'a, '_>,
+    token_payment: Option<RoyaltyTokenPayment<'a, '_>>,
+) -> Result<u64, ProgramError> {
+    let PayCreatorRoyaltiesParams {
+        metadata,
+        mint,
+        price,
+        payer,
+        payer_signer_seeds,
+        creator_accounts,
+        system_program,
+    } = params;
+
+    assert_metadata_account(&metadata, mint.key)?;
+    let plan = {
+        let data = metadata.try_borrow_data()?;
+        plan_creator_royalties(&MetadataRef::unpack(&data)?, price)
+            .ok_or(ProgramError::InvalidAccountData)?
+    };
+
+    let accounts_per_creator = if token_payment.is_some() { 2 } else { 1 };
+    if creator_accounts.len() < plan.payouts.len() * accounts_per_creator {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+    if token_payment.is_none() && payer.lamports() < plan.total_paid() {
+        return Err(ProgramError::InsufficientFunds);
+    }
+
+    let mut seeds: Vec<&[&[u8]]> = vec![];
+    if let Some(seed) = payer_signer_seeds {
+        seeds.push(seed);
+    }
+    for (payout, accounts) in plan
+        .payouts
+        .iter()
+        .zip(creator_accounts.chunks_exact(accounts_per_creator))
+    {
+        let creator = &accounts[0];
+        if !cmp_pubkeys(creator.key, &payout.recipient) {
+            msg!("Expected creator {}, got {}", payout.recipient, creator.key);
+            return Err(ProgramError::InvalidArgument);
+        }
+        if payout.amount == 0 {
+            continue;
+        }
+
+        match &token_payment {
+            None => invoke_signed(
+                &system_instruction::transfer(payer.key, creator.key, payout.amount),
+                &[payer.clone(), creator.clone(), system_program.clone()],
+                seeds.as_slice(),
+            )?,
+            Some(token_payment) => transfer_to_wallet(TransferToWalletParams {
+                mint: token_payment.mint.clone(),
+                source: token_payment.source.clone(),
+                wallet: creator.clone(),
+                wallet_token_account: accounts[1].clone(),
+                amount: payout.amount,
+                authority: token_payment.authority.clone(),
+                authority_signer_seeds: token_payment.authority_signer_seeds,
+                payer: payer.clone(),
+                payer_signer_seeds,
+                system_program: system_program.clone(),
+                token_program: token_payment.token_program.clone(),
+                associated_token_program: token_payment.associated_token_program.clone(),
+            })?,
+        }
+    }
+
+    Ok(plan.remainder)
+}
+
+/// PayCreatorRoyaltiesParams
+pub struct PayCreatorRoyaltiesParams<'a: 'b, 'b> {
+    /// Metadata of the NFT sold.
+    pub metadata: AccountInfo<'a>,
+    /// Mint of the NFT sold, `metadata` must be its Metadata PDA.
+    pub mint: AccountInfo<'a>,
+    /// Sale price, in lamports or base token units.
+    pub price: u64,
+    /// Pays the royalties in SOL, or the creators' token account rent in tokens.
+    pub payer: AccountInfo<'a>,
+    /// payer_signer_seeds
+    pub payer_signer_seeds: Option<&'b [&'b [u8]]>,
+    /// Usually the instruction's remaining accounts.
+    pub creator_accounts: &'b [AccountInfo<'a>],
+    /// system_program
+    pub system_program: AccountInfo<'a>,
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_program::pubkey::Pubkey;
+
+    use super::*;
+    use crate::metadata::{
+        find_metadata_address, tests::metadata_data, Creator, TOKEN_METADATA_PROGRAM_ID,
+    };
+
+    fn creators() -> Vec<Creator> {
+        [33, 33, 34]
+            .into_iter()
+            .map(|share| Creator {
+                address: Pubkey::new_unique(),
+                verified: share == 34,
+                share,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn auction_house_payout_plan() {
+        let creators = creators();
+        let data = metadata_data(&Pubkey::new_unique(), &creators, None, None);
+        let metadata = MetadataRef::unpack(&data).unwrap();
+
+        // 5% of 1_000_020 is 50_001, split 33/33/34 that's 16_500.33, 16_500.33
+        // and 17_000.34: the dust goes to the seller.
+        let plan = plan_creator_royalties(&metadata, 1_000_020).unwrap();
+        let amounts: Vec<u64> = plan.payouts.iter().map(|payout| payout.amount).collect();
+        assert_eq!(amounts, vec![16_500, 16_500, 17_000]);
+        assert_eq!(plan.payouts[2].recipient, creators[2].address);
+        assert_eq!(plan.remainder, 950_020);
+
+        let plan = plan_creator_royalties(&metadata, 19).unwrap();
+        assert_eq!(plan.total_paid(), 0);
+        assert_eq!(plan.remainder, 19);
+
+        let data = metadata_data(&Pubkey::new_unique(), &[], None, None);
+        let plan = plan_creator_royalties(&MetadataRef::unpack(&data).unwrap(), 1_000).unwrap();
+        assert!(plan.payouts.is_empty());
+        assert_eq!(plan.remainder, 1_000);
+    }
+
+    #[test]
+    fn rejects_metadata_of_another_mint() {
+        let mint = Pubkey::new_unique();
+        let other_mint = Pubkey::new_unique();
+        let (metadata_key, _) = find_metadata_address(&mint);
+        let mut data = metadata_data(&mint, &creators(), None, None);
+        let mut lamports = 0;
+        let metadata = AccountInfo::new(
+            &metadata_key,
+            false,
+            false,
+            &mut lamports,
+            &mut data,
+            &TOKEN_METADATA_PROGRAM_ID,
+            false,
+        );
+        let mut mint_lamports = 0;
+        let mint_info = AccountInfo::new(
+            &other_mint,
+            false,
+            false,
+            &mut mint_lamports,
+            &mut [],
+            &TOKEN_METADATA_PROGRAM_ID,
+            false,
+        );
+        let mut payer_lamports = 1_000_000;
+        let payer_key = Pubkey::new_unique();
+        let payer = AccountInfo::new(
+            &payer_key,
+            true,
+            true,
+            &mut payer_lamports,
+            &mut [],
+            &TOKEN_METADATA_PROGRAM_ID,
+            false,
+        );
+
+        let result = pay_creator_royalties(
+            PayCreatorRoyaltiesParams {
+                metadata: metadata.clone(),
+                mint: mint_info,
+                price: 1_000,
+                payer: payer.clone(),
+                payer_signer_seeds: None,
+                creator_accounts: &[],
+                system_program: payer,
+            },
+            None,
+        );
+        assert_eq!(result, Err(ProgramError::InvalidSeeds));
+    }
+}
@@ -0,0 +1,367 @@
+use solana_program::{program_error::ProgramError, pubkey, pubkey::Pubkey};
+
+use crate::borsh_reader::BorshReader;
+
+pub const TOKEN_METADATA_PROGRAM_ID: Pubkey =
+    pubkey!("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+
+/// First seed of every Token Metadata PDA.
+pub const PREFIX: &str = "metadata";
+
+/// Size of a borsh-encoded `Creator`: address(32), verified(1), share(1).
+pub const CREATOR_LEN: usize = 34;
+
+/// Account discriminator stored in the first byte of Token Metadata accounts.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Key {
+    Uninitialized,
+    EditionV1,
+    MasterEditionV1,
+    ReservationListV1,
+    MetadataV1,
+    ReservationListV2,
+    MasterEditionV2,
+    EditionMarker,
+    UseAuthorityRecord,
+    CollectionAuthorityRecord,
+    TokenOwnedEscrow,
+    TokenRecord,
+    MetadataDelegate,
+    EditionMarkerV2,
+    HolderDelegate,
+}
+
+impl TryFrom<u8> for Key {
+    type Error = ProgramError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        Ok(match value {
+            0 => Self::Uninitialized,
+            1 => Self::EditionV1,
+            2 => Self::MasterEditionV1,
+            3 => Self::ReservationListV1,
+            4 => Self::MetadataV1,
+            5 => Self::ReservationListV2,
+            6 => Self::MasterEditionV2,
+            7 => Self::EditionMarker,
+            8 => Self::UseAuthorityRecord,
+            9 => Self::CollectionAuthorityRecord,
+            10 => Self::TokenOwnedEscrow,
+            11 => Self::TokenRecord,
+            12 => Self::MetadataDelegate,
+            13 => Self::EditionMarkerV2,
+            14 => Self::HolderDelegate,
+            _ => return Err(ProgramError::InvalidAccountData),
+        })
+    }
+}
+
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TokenStandard {
+    NonFungible,
+    FungibleAsset,
+    Fungible,
+    NonFungibleEdition,
+    ProgrammableNonFungible,
+    ProgrammableNonFungibleEdition,
+}
+
+impl TryFrom<u8> for TokenStandard {
+    type Error = ProgramError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        Ok(match value {
+            0 => Self::NonFungible,
+            1 => Self::FungibleAsset,
+            2 => Self::Fungible,
+            3 => Self::NonFungibleEdition,
+            4 => Self::ProgrammableNonFungible,
+            5 => Self::ProgrammableNonFungibleEdition,
+            _ => return Err(ProgramError::InvalidAccountData),
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Creator {
+    /// address
+    pub address: Pubkey,
+    /// verified
+    pub verified: bool,
+    /// Percentage of the royalties, all shares add up to 100.
+    pub share: u8,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Collection {
+    /// verified
+    pub verified: bool,
+    /// Mint of the collection NFT.
+    pub key: Pubkey,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CollectionDetails {
+    V1 { size: u64 },
+    V2,
+}
+
+/// Zero-copy view over a Token Metadata `Metadata` account. Strings and creators
+/// borrow from the account data; each read is bounds-checked. Fields added after
+/// `is_mutable` read as `None` on accounts created before they existed.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MetadataRef<'data> {
+    /// update_authority
+    pub update_authority: Pubkey,
+    /// mint
+    pub mint: Pubkey,
+    /// Name as stored, including any trailing `\0` padding.
+    pub name: &'data str,
+    /// Symbol as stored, including any trailing `\0` padding.
+    pub symbol: &'data str,
+    /// URI as stored, including any trailing `\0` padding.
+    pub uri: &'data str,
+    /// seller_fee_basis_points
+    pub seller_fee_basis_points: u16,
+    creators: Option<&'data [u8]>,
+    /// primary_sale_happened
+    pub primary_sale_happened: bool,
+    /// is_mutable
+    pub is_mutable: bool,
+    /// edition_nonce
+    pub edition_nonce: Option<u8>,
+    /// token_standard
+    pub token_standard: Option<TokenStandard>,
+    /// collection
+    pub collection: Option<Collection>,
+    /// collection_details
+    pub collection_details: Option<CollectionDetails>,
+    /// Authorization rule set of a programmable NFT.
+    pub rule_set: Option<Pubkey>,
+}
+
+impl<'data> MetadataRef<'data> {
+    /// Parses the account data, checking the `Key` byte.
+    pub fn unpack(data: &'data [u8]) -> Result<Self, ProgramError> {
+        let mut reader = BorshReader { data };
+        if Key::try_from(reader.read_u8()?)? != Key::MetadataV1 {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        let update_authority = reader.read_pubkey()?;
+        let mint = reader.read_pubkey()?;
+        let name = reader.read_str()?;
+        let symbol = reader.read_str()?;
+        let uri = reader.read_str()?;
+        let seller_fee_basis_points = reader.read_u16()?;
+        let creators = if reader.read_option()? {
+            let len = reader.read_u32()? as usize;
+            let creators_len = len
+                .checked_mul(CREATOR_LEN)
+                .ok_or(ProgramError::InvalidAccountData)?;
+            Some(reader.read_bytes(creators_len)?)
+        } else {
+            None
+        };
+        let primary_sale_happened = reader.read_bool()?;
+        let is_mutable = reader.read_bool()?;
+
+        let edition_nonce = match reader.read_trailing_option()? {
+            true => Some(reader.read_u8()?),
+            false => None,
+        };
+        let token_standard = match reader.read_trailing_option()? {
+            true => Some(TokenStandard::try_from(reader.read_u8()?)?),
+            false => None,
+        };
+        let collection = match reader.read_trailing_option()? {
+            true => Some(Collection {
+                verified: reader.read_bool()?,
+                key: reader.read_pubkey()?,
+            }),
+            false => None,
+        };
+        // Uses: use_method(1), remaining(8), total(8).
+        if reader.read_trailing_option()? {
+            reader.read_bytes(17)?;
+        }
+        let collection_details = match reader.read_trailing_option()? {
+            true => match reader.read_u8()? {
+                0 => Some(CollectionDetails::V1 {
+                    size: reader.read_u64()?,
+                }),
+                1 => {
+                    reader.read_bytes(8)?;
+                    Some(CollectionDetails::V2)
+                }
+                _ => return Err(ProgramError::InvalidAccountData),
+            },
+            false => None,
+        };
+        let rule_set = match reader.read_trailing_option()? {
+            // ProgrammableConfig::V1 is the only variant.
+            true => match reader.read_u8()? {
+                0 if reader.read_option()? => Some(reader.read_pubkey()?),
+                0 => None,
+                _ => return Err(ProgramError::InvalidAccountData),
+            },
+            false => None,
+        };
+
+        Ok(Self {
+            update_authority,
+            mint,
+            name,
+            symbol,
+            uri,
+            seller_fee_basis_points,
+            creators,
+            primary_sale_happened,
+            is_mutable,
+            edition_nonce,
+            token_standard,
+            collection,
+            collection_details,
+            rule_set,
+        })
+    }
+
+    /// Iterates the creators in order, empty if the metadata has none.
+    pub fn creators(&self) -> impl ExactSizeIterator<Item = Creator> + 'data {
+        self.creators
+            .unwrap_or_default()
+            .chunks_exact(CREATOR_LEN)
+            .map(|creator| Creator {
+                address: Pubkey::new_from_array(creator[..32].try_into().unwrap()),
+                verified: creator[32] != 0,
+                share: creator[33],
+            })
+    }
+
+    /// Returns true for programmable NFTs and their editions.
+    pub fn is_programmable(&self) -> bool {
+        matches!(
+            self.token_standard,
+            Some(
+                TokenStandard::ProgrammableNonFungible
+                    | TokenStandard::ProgrammableNonFungibleEdition
+            )
+        )
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// Borsh-encodes a Metadata account the way Token Metadata lays it out.
+    pub(crate) fn metadata_data(
+        mint: &Pubkey,
+        creators: &[Creator],
+        token_standard: Option<TokenStandard>,
+        collection: Option<Collection>,
+    ) -> Vec<u8> {
+        let mut data = vec![Key::MetadataV1 as u8];
+        data.extend_from_slice(Pubkey::new_unique().as_ref());
+        data.extend_from_slice(mint.as_ref());
+        for value in ["Name\0\0", "SYM", "https://example.com"] {
+            data.extend_from_slice(&(value.len() as u32).to_le_bytes());
+            data.extend_from_slice(value.as_bytes());
+        }
+        data.extend_from_slice(&500u16.to_le_bytes());
+        if creators.is_empty() {
+            data.push(0);
+        } else {
+            data.push(1);
+            data.extend_from_slice(&(creators.len() as u32).to_le_bytes());
+            for creator in creators {
+                data.extend_from_slice(creator.address.as_ref());
+                data.push(creator.verified as u8);
+                data.push(creator.share);
+            }
+        }
+        // primary_sale_happened, is_mutable, edition_nonce.
+        data.extend_from_slice(&[0, 1, 1, 255]);
+        match token_standard {
+            Some(token_standard) => data.extend_from_slice(&[1, token_standard as u8]),
+            None => data.push(0),
+        }
+        match collection {
+            Some(collection) => {
+                data.extend_from_slice(&[1, collection.verified as u8]);
+                data.extend_from_slice(collection.key.as_ref());
+            }
+            None => data.push(0),
+        }
+        // uses, then collection_details V1 and programmable_config V1 without a rule set.
+        data.push(0);
+        data.extend_from_slice(&[1, 0]);
+        data.extend_from_slice(&42u64.to_le_bytes());
+        data.extend_from_slice(&[1, 0, 0]);
+        // Token Metadata allocates the maximum size and zero-pads the rest.
+        data.resize(679, 0);
+        data
+    }
+
+    #[test]
+    fn unpack_reads_fields() {
+        let mint = Pubkey::new_unique();
+        let creator = Creator {
+            address: Pubkey::new_unique(),
+            verified: true,
+            share: 100,
+        };
+        let collection = Collection {
+            verified: true,
+            key: Pubkey::new_unique(),
+        };
+        let data = metadata_data(
+            &mint,
+            &[creator],
+            Some(TokenStandard::ProgrammableNonFungible),
+            Some(collection),
+        );
+        let metadata = MetadataRef::unpack(&data).unwrap();
+
+        assert_eq!(metadata.mint, mint);
+        assert_eq!(metadata.name, "Name\0\0");
+        assert_eq!(metadata.uri, "https://example.com");
+        assert_eq!(metadata.seller_fee_basis_points, 500);
+        assert_eq!(metadata.creators().collect::<Vec<_>>(), vec![creator]);
+        assert!(!metadata.primary_sale_happened);
+        assert!(metadata.is_mutable);
+        assert_eq!(metadata.edition_nonce, Some(255));
+        assert!(metadata.is_programmable());
+        assert_eq!(metadata.collection, Some(collection));
+        assert_eq!(
+            metadata.collection_details,
+            Some(CollectionDetails::V1 { size: 42 })
+        );
+        assert_eq!(metadata.rule_set, None);
+    }
+
+    #[test]
+    fn unpack_handles_old_and_invalid_accounts() {
+        let mint = Pubkey::new_unique();
+        let data = metadata_data(&mint, &[], None, None);
+
+        // Accounts created before edition_nonce end right after is_mutable.
+        let end_of_v1 = 1 + 32 + 32 + (4 + 6) + (4 + 3) + (4 + 19) + 2 + 1 + 2;
+        let metadata = MetadataRef::unpack(&data[..end_of_v1]).unwrap();
+        assert_eq!(metadata.creators().len(), 0);
+        assert_eq!(metadata.edition_nonce, None);
+        assert_eq!(metadata.collection_details, None);
+
+        assert!(MetadataRef::unpack(&data[..end_of_v1 - 1]).is_err());
+        assert!(MetadataRef::unpack(&[]).is_err());
+
+        let mut wrong_key = data.clone();
+        wrong_key[0] = Key::MasterEditionV2 as u8;
+        assert!(MetadataRef::unpack(&wrong_key).is_err());
+
+        let mut wrong_token_standard = data;
+        wrong_token_standard[end_of_v1 + 2..end_of_v1 + 4].copy_from_slice(&[1, 9]);
+        assert!(MetadataRef::unpack(&wrong_token_standard).is_err());
+    }
+}
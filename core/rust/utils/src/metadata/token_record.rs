@@ -0,0 +1,310 @@
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, msg, program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+use super::{assert_token_metadata_account, Key, PREFIX, TOKEN_METADATA_PROGRAM_ID};
+use crate::{assert_derivation, borsh_reader::BorshReader};
+
+/// Seed of the TokenRecord PDA, between the mint and the token account.
+pub const TOKEN_RECORD_SEED: &str = "token_record";
+
+/// Lock state of a programmable NFT.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TokenState {
+    Unlocked,
+    Locked,
+    /// A sale delegate is set, only it can transfer the token.
+    Listed,
+}
+
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TokenDelegateRole {
+    Sale,
+    Transfer,
+    Utility,
+    Staking,
+    Standard,
+    LockedTransfer,
+    Migration,
+}
+
+/// Per token account state of a programmable NFT, which takes precedence over
+/// the SPL token account's own delegate and frozen state.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TokenRecord {
+    /// bump
+    pub bump: u8,
+    /// state
+    pub state: TokenState,
+    /// rule_set_revision
+    pub rule_set_revision: Option<u64>,
+    /// delegate
+    pub delegate: Option<Pubkey>,
+    /// delegate_role
+    pub delegate_role: Option<TokenDelegateRole>,
+    /// Only address a LockedTransfer delegate may transfer to.
+    pub locked_transfer: Option<Pubkey>,
+}
+
+impl TokenRecord {
+    /// Parses the account data, checking the `Key` byte.
+    pub fn unpack(data: &[u8]) -> Result<Self, ProgramError> {
+        let mut reader = BorshReader { data };
+        if Key::try_from(reader.read_u8()?)? != Key::TokenRecord {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        let bump = reader.read_u8()?;
+        let state = match reader.read_u8()? {
+            0 => TokenState::Unlocked,
+            1 => TokenState::Locked,
+            2 => TokenState::Listed,
+            _ => return Err(ProgramError::InvalidAccountData),
+        };
+        let rule_set_revision = match reader.read_option()? {
+            true => Some(reader.read_u64()?),
+            false => None,
+        };
+        let delegate = match reader.read_option()? {
+            true => Some(reader.read_pubkey()?),
+            false => None,
+        };
+        let delegate_role = match reader.read_option()? {
+            true => Some(match reader.read_u8()? {
+                0 => TokenDelegateRole::Sale,
+                1 => TokenDelegateRole::Transfer,
+                2 => TokenDelegateRole::Utility,
+                3 => TokenDelegateRole::Staking,
+                4 => TokenDelegateRole::Standard,
+                5 => TokenDelegateRole::LockedTransfer,
+                6 => TokenDelegateRole::Migration,
+                _ => return Err(ProgramError::InvalidAccountData),
+            }),
+            false => None,
+        };
+        let locked_transfer = match reader.read_trailing_option()? {
+            true => Some(reader.read_pubkey()?),
+            false => None,
+        };
+
+        Ok(Self {
+            bump,
+            state,
+            rule_set_revision,
+            delegate,
+            delegate_role,
+            locked_transfer,
+        })
+    }
+}
+
+pub fn find_token_record_address(mint: &Pubkey, token: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PREFIX.as_bytes(),
+            TOKEN_METADATA_PROGRAM_ID.as_ref(),
+            mint.as_ref(),
+            TOKEN_RECORD_SEED.as_bytes(),
+            token.as_ref(),
+        ],
+        &TOKEN_METADATA_PROGRAM_ID,
+    )
+}
+
+/// Checks the owner and `Key` byte of the token record, then reads it.
+pub fn get_token_record(token_record_info: &AccountInfo) -> Result<TokenRecord, ProgramError> {
+    assert_token_metadata_account(token_record_info, Key::TokenRecord)?;
+    let data = token_record_info.try_borrow_data()?;
+
+    TokenRecord::unpack(&data)
+}
+
+/// Asserts that the account is the TokenRecord PDA of the token account
+/// `token` for `mint`, owned by Token Metadata. Returns the PDA bump.
+pub fn assert_token_record_account(
+    token_record_info: &AccountInfo,
+    mint: &Pubkey,
+    token: &Pubkey,
+) -> Result<u8, ProgramError> {
+    let bump = assert_derivation(
+        &TOKEN_METADATA_PROGRAM_ID,
+        token_record_info,
+        &[
+            PREFIX.as_bytes(),
+            TOKEN_METADATA_PROGRAM_ID.as_ref(),
+            mint.as_ref(),
+            TOKEN_RECORD_SEED.as_bytes(),
+            token.as_ref(),
+        ],
+        ProgramError::InvalidSeeds,
+    )?;
+    assert_token_metadata_account(token_record_info, Key::TokenRecord)?;
+
+    Ok(bump)
+}
+
+/// Asserts that the account is the token record of the token account `token`
+/// for `mint`, then reads it.
+fn get_token_record_for(
+    token_record_info: &AccountInfo,
+    mint: &Pubkey,
+    token: &Pubkey,
+) -> Result<TokenRecord, ProgramError> {
+    assert_token_record_account(token_record_info, mint, token)?;
+    let data = token_record_info.try_borrow_data()?;
+
+    TokenRecord::unpack(&data)
+}
+
+/// Asserts that the programmable NFT held in the token account `token` is
+/// neither locked nor listed, according to its token record.
+pub fn assert_pnft_unlocked(
+    token_record_info: &AccountInfo,
+    mint: &Pubkey,
+    token: &Pubkey,
+) -> ProgramResult {
+    let token_record = get_token_record_for(token_record_info, mint, token)?;
+    if token_record.state != TokenState::Unlocked {
+        msg!("Token is {:?}", token_record.state);
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    Ok(())
+}
+
+/// Asserts that `delegate` is the token delegate recorded in the token record
+/// of the token account `token` for `mint`, with the given role.
+pub fn assert_token_delegate_role(
+    token_record_info: &AccountInfo,
+    mint: &Pubkey,
+    token: &Pubkey,
+    delegate: &Pubkey,
+    role: TokenDelegateRole,
+) -> ProgramResult {
+    let token_record = get_token_record_for(token_record_info, mint, token)?;
+    if token_record.delegate != Some(*delegate) || token_record.delegate_role != Some(role) {
+        msg!("{} is not a {:?} delegate", delegate, role);
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn token_record_data(
+        state: TokenState,
+        delegate: Option<(Pubkey, TokenDelegateRole)>,
+    ) -> Vec<u8> {
+        let mut data = vec![Key::TokenRecord as u8, 254, state as u8, 0];
+        match delegate {
+            Some((delegate, role)) => {
+                data.push(1);
+                data.extend_from_slice(delegate.as_ref());
+                data.extend_from_slice(&[1, role as u8]);
+            }
+            None => data.extend_from_slice(&[0, 0]),
+        }
+        data.push(0);
+        data
+    }
+
+    #[test]
+    fn unpack_token_record() {
+        let delegate = Pubkey::new_unique();
+        let data = token_record_data(
+            TokenState::Locked,
+            Some((delegate, TokenDelegateRole::Staking)),
+        );
+        let token_record = TokenRecord::unpack(&data).unwrap();
+
+        assert_eq!(token_record.bump, 254);
+        assert_eq!(token_record.state, TokenState::Locked);
+        assert_eq!(token_record.rule_set_revision, None);
+        assert_eq!(token_record.delegate, Some(delegate));
+        assert_eq!(token_record.delegate_role, Some(TokenDelegateRole::Staking));
+        assert_eq!(token_record.locked_transfer, None);
+
+        // Records created before LockedTransfer delegates end after delegate_role.
+        assert!(TokenRecord::unpack(&data[..data.len() - 1]).is_ok());
+        assert!(TokenRecord::unpack(&data[..data.len() - 2]).is_err());
+
+        let mut wrong_state = data;
+        wrong_state[2] = 3;
+        assert!(TokenRecord::unpack(&wrong_state).is_err());
+    }
+
+    #[test]
+    fn token_record_assertions() {
+        let mint = Pubkey::new_unique();
+        let token = Pubkey::new_unique();
+        let delegate = Pubkey::new_unique();
+        let (token_record_key, _) = find_token_record_address(&mint, &token);
+        let mut data = token_record_data(
+            TokenState::Unlocked,
+            Some((delegate, TokenDelegateRole::Utility)),
+        );
+        let mut lamports = 0;
+        let token_record_info = AccountInfo::new(
+            &token_record_key,
+            false,
+            false,
+            &mut lamports,
+            &mut data,
+            &TOKEN_METADATA_PROGRAM_ID,
+            false,
+        );
+
+        assert!(assert_token_record_account(&token_record_info, &mint, &token).is_ok());
+        assert_eq!(
+            assert_token_record_account(&token_record_info, &token, &mint),
+            Err(ProgramError::InvalidSeeds)
+        );
+        assert!(assert_pnft_unlocked(&token_record_info, &mint, &token).is_ok());
+        assert_eq!(
+            assert_pnft_unlocked(&token_record_info, &mint, &Pubkey::new_unique()),
+            Err(ProgramError::InvalidSeeds)
+        );
+        assert!(assert_token_delegate_role(
+            &token_record_info,
+            &mint,
+            &token,
+            &delegate,
+            TokenDelegateRole::Utility
+        )
+        .is_ok());
+        assert_eq!(
+            assert_token_delegate_role(
+                &token_record_info,
+                &Pubkey::new_unique(),
+                &token,
+                &delegate,
+                TokenDelegateRole::Utility
+            ),
+            Err(ProgramError::InvalidSeeds)
+        );
+        assert!(assert_token_delegate_role(
+            &token_record_info,
+            &mint,
+            &token,
+            &delegate,
+            TokenDelegateRole::Sale
+        )
+        .is_err());
+        assert!(assert_token_delegate_role(
+            &token_record_info,
+            &mint,
+            &token,
+            &Pubkey::new_unique(),
+            TokenDelegateRole::Utility
+        )
+        .is_err());
+
+        token_record_info.try_borrow_mut_data().unwrap()[2] = TokenState::Listed as u8;
+        assert!(assert_pnft_unlocked(&token_record_info, &mint, &token).is_err());
+    }
+}
@@ -0,0 +1,56 @@
+use arrayref::array_ref;
+use solana_program::{account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey};
+
+use super::{Key, MetadataRef, TokenStandard, PREFIX, TOKEN_METADATA_PROGRAM_ID};
+
+pub fn find_metadata_address(mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PREFIX.as_bytes(),
+            TOKEN_METADATA_PROGRAM_ID.as_ref(),
+            mint.as_ref(),
+        ],
+        &TOKEN_METADATA_PROGRAM_ID,
+    )
+}
+
+/// Reads the `Key` byte of a Token Metadata account.
+pub fn get_key(account_info: &AccountInfo) -> Result<Key, ProgramError> {
+    let data = account_info.try_borrow_data()?;
+    let key = data.first().ok_or(ProgramError::InvalidAccountData)?;
+
+    Key::try_from(*key)
+}
+
+/// Cheap method to just grab the update authority from a Metadata account,
+/// instead of deserializing the entire thing.
+pub fn get_metadata_update_authority(metadata_info: &AccountInfo) -> Result<Pubkey, ProgramError> {
+    // Metadata layout: key(1), update_authority(32), mint(32), ...
+    let data = metadata_info.try_borrow_data()?;
+    if data.len() < 65 {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    Ok(Pubkey::new_from_array(*array_ref![data, 1, 32]))
+}
+
+/// Cheap method to just grab the mint from a Metadata account.
+pub fn get_metadata_mint(metadata_info: &AccountInfo) -> Result<Pubkey, ProgramError> {
+    let data = metadata_info.try_borrow_data()?;
+    if data.len() < 65 {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    Ok(Pubkey::new_from_array(*array_ref![data, 33, 32]))
+}
+
+/// Reads the token standard, so callers can branch between the NFT and pNFT
+/// paths. `None` for metadata created before token standards existed, which
+/// are regular NFTs or fungibles depending on the mint.
+pub fn get_token_standard(
+    metadata_info: &AccountInfo,
+) -> Result<Option<TokenStandard>, ProgramError> {
+    let data = metadata_info.try_borrow_data()?;
+
+    Ok(MetadataRef::unpack(&data)?.token_standard)
+}
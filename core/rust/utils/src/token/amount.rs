@@ -0,0 +1,265 @@
+use std::fmt;
+
+use solana_program::{account_info::AccountInfo, program_error::ProgramError};
+use spl_token_2022::{
+    extension::{
+        interest_bearing_mint::InterestBearingConfig, scaled_ui_amount::ScaledUiAmountConfig,
+        BaseStateWithExtensions,
+    },
+    state::Mint,
+};
+
+use super::{get_mint_decimals, unpack};
+
+/// Raw token amount together with the decimals of its mint. Arithmetic is
+/// checked and only defined between amounts with the same decimals.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct TokenAmount {
+    /// Amount in base units, as stored in token accounts.
+    pub raw: u64,
+    /// decimals
+    pub decimals: u8,
+}
+
+impl TokenAmount {
+    pub const fn new(raw: u64, decimals: u8) -> Self {
+        Self { raw, decimals }
+    }
+
+    /// Pairs `raw` with the decimals read from the mint account.
+    pub fn from_mint(raw: u64, mint_info: &AccountInfo) -> Result<Self, ProgramError> {
+        Ok(Self::new(raw, get_mint_decimals(mint_info)?))
+    }
+
+    /// Parses a decimal string such as `"12.5"` into base units, without going
+    /// through floating point. Fails if it has more fractional digits than `decimals`.
+    pub fn parse(ui_amount: &str, decimals: u8) -> Result<Self, ProgramError> {
+        let (integer, fraction) = ui_amount.split_once('.').unwrap_or((ui_amount, ""));
+        let decimals_len = decimals as usize;
+        let is_digits = |part: &str| part.bytes().all(|byte| byte.is_ascii_digit());
+        if (integer.is_empty() && fraction.is_empty())
+            || !is_digits(integer)
+            || !is_digits(fraction)
+            || fraction.len() > decimals_len
+        {
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        let mut digits = String::with_capacity(integer.len() + decimals_len);
+        digits.push_str(integer);
+        digits.push_str(fraction);
+        digits.extend(std::iter::repeat_n('0', decimals_len - fraction.len()));
+        let raw = digits
+            .parse::<u64>()
+            .map_err(|_| ProgramError::InvalidArgument)?;
+
+        Ok(Self::new(raw, decimals))
+    }
+
+    pub fn checked_add(self, other: Self) -> Option<Self> {
+        if self.decimals != other.decimals {
+            return None;
+        }
+        Some(Self::new(self.raw.checked_add(other.raw)?, self.decimals))
+    }
+
+    pub fn checked_sub(self, other: Self) -> Option<Self> {
+        if self.decimals != other.decimals {
+            return None;
+        }
+        Some(Self::new(self.raw.checked_sub(other.raw)?, self.decimals))
+    }
+
+    /// Computes `raw * numerator / denominator`, rounding down, with a 128-bit
+    /// intermediate so the product can't overflow.
+    pub fn checked_mul_div(self, numerator: u64, denominator: u64) -> Option<Self> {
+        let raw = (self.raw as u128)
+            .checked_mul(numerator as u128)?
+            .checked_div(denominator as u128)?;
+        Some(Self::new(u64::try_from(raw).ok()?, self.decimals))
+    }
+
+    /// Exact UI representation of the raw amount, with trailing zeros trimmed.
+    pub fn to_ui_amount_string(&self) -> String {
+        let decimals = self.decimals as usize;
+        let digits = format!("{:0>width$}", self.raw, width = decimals + 1);
+        let (integer, fraction) = digits.split_at(digits.len() - decimals);
+        let fraction = fraction.trim_end_matches('0');
+
+        if fraction.is_empty() {
+            integer.to_string()
+        } else {
+            format!("{integer}.{fraction}")
+        }
+    }
+
+    /// UI representation as wallets show it, applying the mint's interest-bearing
+    /// or scaled UI amount extension at `unix_timestamp`. Falls back to
+    /// `to_ui_amount_string` for mints with neither extension.
+    pub fn to_ui_amount_string_for_mint(
+        &self,
+        mint_data: &[u8],
+        unix_timestamp: i64,
+    ) -> Result<String, ProgramError> {
+        let mint = unpack::<Mint>(mint_data)?;
+        if mint.base.decimals != self.decimals {
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        let ui_amount = if let Ok(config) = mint.get_extension::<InterestBearingConfig>() {
+            config.amount_to_ui_amount(self.raw, self.decimals, unix_timestamp)
+        } else if let Ok(config) = mint.get_extension::<ScaledUiAmountConfig>() {
+            config.amount_to_ui_amount(self.raw, self.decimals, unix_timestamp)
+        } else {
+            Some(self.to_ui_amount_string())
+        };
+
+        ui_amount.ok_or(ProgramError::ArithmeticOverflow)
+    }
+}
+
+impl fmt::Display for TokenAmount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_ui_amount_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytemuck::Pod;
+    use solana_program::program_pack::Pack;
+    use spl_token_2022::extension::{
+        BaseStateWithExtensionsMut, Extension, ExtensionType, StateWithExtensionsMut,
+    };
+
+    use super::*;
+
+    /// Mint with 6 decimals and the single extension `T`, set up by `init`.
+    fn mint_with_extension<T: Extension + Pod + Default>(init: impl FnOnce(&mut T)) -> Vec<u8> {
+        let space = ExtensionType::try_calculate_account_len::<Mint>(&[T::TYPE]).unwrap();
+        let mut data = vec![0; space];
+        let mut mint = StateWithExtensionsMut::<Mint>::unpack_uninitialized(&mut data).unwrap();
+        mint.base = Mint {
+            decimals: 6,
+            is_initialized: true,
+            ..Default::default()
+        };
+        mint.pack_base();
+        mint.init_account_type().unwrap();
+        init(mint.init_extension::<T>(true).unwrap());
+        data
+    }
+
+    #[test]
+    fn parse_and_format_round_trip() {
+        for (ui_amount, decimals, raw) in [
+            ("0", 6, 0),
+            ("1", 0, 1),
+            ("1.5", 6, 1_500_000),
+            ("0.000001", 6, 1),
+            (".25", 2, 25),
+            ("18446744073709551615", 0, u64::MAX),
+        ] {
+            let amount = TokenAmount::parse(ui_amount, decimals).unwrap();
+            assert_eq!(amount, TokenAmount::new(raw, decimals));
+            assert_eq!(
+                amount.to_string(),
+                ui_amount
+                    .strip_prefix('.')
+                    .map_or(ui_amount.to_string(), |rest| format!("0.{rest}"))
+            );
+        }
+        assert_eq!(TokenAmount::parse("2.", 9).unwrap().to_string(), "2");
+    }
+
+    #[test]
+    fn parse_rejects_invalid_input() {
+        for ui_amount in [
+            "",
+            ".",
+            "1.2.3",
+            "-1",
+            "+1",
+            "1e3",
+            "0.1234567",
+            "18446744073709551616",
+        ] {
+            assert!(TokenAmount::parse(ui_amount, 6).is_err(), "{ui_amount}");
+        }
+    }
+
+    #[test]
+    fn checked_arithmetic() {
+        let one = TokenAmount::new(1_000_000, 6);
+
+        assert_eq!(one.checked_add(one), Some(TokenAmount::new(2_000_000, 6)));
+        assert_eq!(
+            one.checked_sub(TokenAmount::new(1, 6)),
+            Some(TokenAmount::new(999_999, 6))
+        );
+        assert_eq!(TokenAmount::new(0, 6).checked_sub(one), None);
+        assert_eq!(one.checked_add(TokenAmount::new(1, 9)), None);
+        assert_eq!(
+            TokenAmount::new(u64::MAX, 0).checked_add(TokenAmount::new(1, 0)),
+            None
+        );
+        assert_eq!(
+            TokenAmount::new(u64::MAX, 0).checked_mul_div(3, 4),
+            Some(TokenAmount::new(13_835_058_055_282_163_711, 0))
+        );
+        assert_eq!(one.checked_mul_div(1, 0), None);
+        assert_eq!(TokenAmount::new(u64::MAX, 0).checked_mul_div(2, 1), None);
+    }
+
+    #[test]
+    fn ui_amount_string_for_mint_applies_extensions() {
+        let amount = TokenAmount::new(1_500_000, 6);
+
+        let mut mint_data = vec![0; Mint::LEN];
+        Mint {
+            decimals: 6,
+            is_initialized: true,
+            ..Default::default()
+        }
+        .pack_into_slice(&mut mint_data);
+        assert_eq!(
+            amount.to_ui_amount_string_for_mint(&mint_data, 0),
+            Ok("1.5".to_string())
+        );
+        assert_eq!(
+            TokenAmount::new(1_500_000, 9).to_ui_amount_string_for_mint(&mint_data, 0),
+            Err(ProgramError::InvalidArgument)
+        );
+
+        // 5% a year, compounded continuously from timestamp 1000.
+        let seconds_per_year = 31_556_736;
+        let mint_data = mint_with_extension::<InterestBearingConfig>(|config| {
+            config.initialization_timestamp = 1_000.into();
+            config.last_update_timestamp = 1_000.into();
+            config.current_rate = 500.into();
+        });
+        assert_eq!(
+            amount.to_ui_amount_string_for_mint(&mint_data, 1_000),
+            Ok("1.5".to_string())
+        );
+        assert_eq!(
+            amount.to_ui_amount_string_for_mint(&mint_data, 1_000 + seconds_per_year),
+            Ok("1.576907".to_string())
+        );
+
+        // Doubled, then tripled from timestamp 1000.
+        let mint_data = mint_with_extension::<ScaledUiAmountConfig>(|config| {
+            config.multiplier = 2.0.into();
+            config.new_multiplier_effective_timestamp = 1_000.into();
+            config.new_multiplier = 3.0.into();
+        });
+        assert_eq!(
+            amount.to_ui_amount_string_for_mint(&mint_data, 999),
+            Ok("3".to_string())
+        );
+        assert_eq!(
+            amount.to_ui_amount_string_for_mint(&mint_data, 1_000),
+            Ok("4.5".to_string())
+        );
+    }
+}
@@ -1,9 +1,13 @@
 use solana_program::{
-    account_info::AccountInfo, entrypoint::ProgramResult, program_error::ProgramError, pubkey,
-    pubkey::Pubkey,
+    account_info::AccountInfo, entrypoint::ProgramResult, msg, program_error::ProgramError,
+    program_option::COption, pubkey, pubkey::Pubkey,
+};
+use spl_token_2022::{
+    extension::{transfer_hook, BaseStateWithExtensions, ExtensionType},
+    state::{Account, AccountState, Mint},
 };
-use spl_token_2022::state::Account;
 
+use super::{unpack, unpack_initialized};
 use crate::assert_initialized;
 
 pub static SPL_TOKEN_PROGRAM_IDS: [Pubkey; 2] = [
@@ -46,6 +50,7 @@ pub fn assert_token_program_matches_package(
 /// * it's owner matches the provided owner
 /// * it's mint matches the provided mint
 /// * it holds more than than 0 tokens of the given mint.
+///
 /// Accepts either an &AccountInfo or an Account for token_account parameter.
 pub fn assert_holder(
     token_account: impl ToTokenAccount,
@@ -69,3 +74,106 @@ pub fn assert_holder(
 
     Ok(())
 }
+
+/// Asserts that the token account is frozen and delegated to `delegate`, as
+/// left by `lock_token_account`.
+pub fn assert_locked_by(
+    token_account_info: &AccountInfo,
+    delegate: &Pubkey,
+    error: impl Into<ProgramError> + Clone,
+) -> ProgramResult {
+    let data = token_account_info.try_borrow_data()?;
+    let token_account = unpack_initialized::<Account>(&data, error.clone())?;
+
+    if token_account.base.delegate != COption::Some(*delegate) {
+        return Err(error.into());
+    }
+
+    if token_account.base.state != AccountState::Frozen {
+        return Err(error.into());
+    }
+
+    Ok(())
+}
+
+/// Mint extensions that let a third party move, freeze or hide escrowed tokens.
+pub const ESCROW_FORBIDDEN_MINT_EXTENSIONS: [ExtensionType; 5] = [
+    ExtensionType::PermanentDelegate,
+    ExtensionType::NonTransferable,
+    ExtensionType::DefaultAccountState,
+    ExtensionType::ConfidentialTransferMint,
+    ExtensionType::Pausable,
+];
+
+/// Extensions a mint may or may not carry, checked by `assert_mint_extension_policy`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct MintExtensionPolicy<'a> {
+    /// If set, every extension on the mint must be listed here.
+    pub allowed: Option<&'a [ExtensionType]>,
+    /// Extensions the mint must not have.
+    pub forbidden: &'a [ExtensionType],
+    /// If set, a TransferHook extension must point at one of these programs.
+    pub transfer_hook_programs: Option<&'a [Pubkey]>,
+}
+
+/// Reason a mint was rejected by `assert_mint_extension_policy`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum MintExtensionPolicyError {
+    /// The mint has an extension listed in `forbidden`.
+    Forbidden(ExtensionType),
+    /// The mint has an extension missing from `allowed`.
+    NotAllowed(ExtensionType),
+    /// The mint's transfer hook program is not in `transfer_hook_programs`.
+    UntrustedTransferHook(Pubkey),
+    /// The mint account could not be unpacked.
+    InvalidMint(ProgramError),
+}
+
+impl From<MintExtensionPolicyError> for ProgramError {
+    fn from(error: MintExtensionPolicyError) -> Self {
+        match error {
+            MintExtensionPolicyError::InvalidMint(error) => error,
+            error => {
+                msg!("Mint extension policy violation: {:?}", error);
+                ProgramError::InvalidAccountData
+            }
+        }
+    }
+}
+
+/// Asserts that the mint's extensions satisfy the given policy. Legacy token
+/// program mints have no extensions and pass any policy.
+pub fn assert_mint_extension_policy(
+    mint_info: &AccountInfo,
+    policy: &MintExtensionPolicy,
+) -> Result<(), MintExtensionPolicyError> {
+    let data = mint_info
+        .try_borrow_data()
+        .map_err(MintExtensionPolicyError::InvalidMint)?;
+    let mint = unpack::<Mint>(&data).map_err(MintExtensionPolicyError::InvalidMint)?;
+    let extension_types = mint
+        .get_extension_types()
+        .map_err(MintExtensionPolicyError::InvalidMint)?;
+
+    for extension_type in extension_types {
+        if policy.forbidden.contains(&extension_type) {
+            return Err(MintExtensionPolicyError::Forbidden(extension_type));
+        }
+        if let Some(allowed) = policy.allowed {
+            if !allowed.contains(&extension_type) {
+                return Err(MintExtensionPolicyError::NotAllowed(extension_type));
+            }
+        }
+    }
+
+    if let (Some(trusted), Some(program_id)) = (
+        policy.transfer_hook_programs,
+        transfer_hook::get_program_id(&mint),
+    ) {
+        if !trusted.contains(&program_id) {
+            return Err(MintExtensionPolicyError::UntrustedTransferHook(program_id));
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,264 @@
+use solana_program::{account_info::AccountInfo, program_error::ProgramError};
+use spl_token_2022::{
+    extension::{transfer_fee::TransferFeeAmount, BaseStateWithExtensions},
+    state::Account,
+};
+
+use super::{
+    get_mint_decimals, spl_token_burn, spl_token_close, spl_token_harvest_withheld_tokens_to_mint,
+    spl_token_transfer_checked_with_hook, unpack_initialized, TokenBurnParams, TokenCloseParams,
+    TokenHarvestWithheldTokensToMintParams, TokenTransferCheckedWithHookParams,
+};
+
+/// What to do with the tokens left in an account before closing it.
+#[derive(Debug)]
+pub enum DrainMode<'a> {
+    /// Burn the remaining balance. Native accounts are closed without burning,
+    /// since the native mint can't be burned and closing releases the lamports.
+    Burn,
+    /// Transfer the remaining balance to this token account.
+    TransferTo(AccountInfo<'a>),
+}
+
+/// Empties a token account and closes it: burns or transfers the remaining
+/// balance, harvests any Token-2022 withheld transfer fees to the mint so the
+/// account becomes closable, then closes it. Transfers go through the mint's
+/// transfer hook, if any. Returns the amount burned or moved, which is 0 when
+/// a native account is closed with `DrainMode::Burn`.
+pub fn drain_and_close_token_account<'a>(
+    params: DrainAndCloseTokenAccountParams<'a, '_>,
+    mode: DrainMode<'a>,
+) -> Result<u64, ProgramError> {
+    let DrainAndCloseTokenAccountParams {
+        account,
+        mint,
+        authority,
+        destination,
+        authority_signer_seeds,
+        token_program,
+        additional_accounts,
+    } = params;
+
+    let (amount, withheld_amount, is_native) = {
+        let data = account.try_borrow_data()?;
+        let state = unpack_initialized::<Account>(&data, ProgramError::UninitializedAccount)?;
+        if state.base.mint != *mint.key {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        let withheld_amount = state
+            .get_extension::<TransferFeeAmount>()
+            .map(|fee_amount| u64::from(fee_amount.withheld_amount))
+            .unwrap_or(0);
+        (state.base.amount, withheld_amount, state.base.is_native())
+    };
+
+    let mut drained = amount;
+    if amount > 0 {
+        match mode {
+            DrainMode::Burn if is_native => drained = 0,
+            DrainMode::Burn => spl_token_burn(TokenBurnParams {
+                mint: mint.clone(),
+                source: account.clone(),
+                amount,
+                authority: authority.clone(),
+                authority_signer_seeds,
+                token_program: token_program.clone(),
+            })?,
+            DrainMode::TransferTo(token_destination) => {
+                spl_token_transfer_checked_with_hook(TokenTransferCheckedWithHookParams {
+                    mint: mint.clone(),
+                    source: account.clone(),
+                    destination: token_destination,
+                    amount,
+                    authority: authority.clone(),
+                    authority_signer_seeds,
+                    token_program: token_program.clone(),
+                    decimals: get_mint_decimals(&mint)?,
+                    additional_accounts,
+                })?
+            }
+        }
+    }
+
+    // Withdrawing the fees would need the mint's withdraw withheld authority to
+    // sign, while harvesting them to the mint is permissionless. The authority
+    // can withdraw them from the mint later.
+    if withheld_amount > 0 {
+        spl_token_harvest_withheld_tokens_to_mint(TokenHarvestWithheldTokensToMintParams {
+            mint,
+            sources: std::slice::from_ref(&account),
+            token_program: token_program.clone(),
+        })?;
+    }
+
+    spl_token_close(TokenCloseParams {
+        account,
+        destination,
+        owner: authority,
+        authority_signer_seeds,
+        token_program,
+    })?;
+
+    Ok(drained)
+}
+
+/// DrainAndCloseTokenAccountParams
+pub struct DrainAndCloseTokenAccountParams<'a: 'b, 'b> {
+    /// Token account to empty and close.
+    pub account: AccountInfo<'a>,
+    /// mint
+    pub mint: AccountInfo<'a>,
+    /// Owner of the token account, also used as close authority.
+    pub authority: AccountInfo<'a>,
+    /// Receives the rent lamports.
+    pub destination: AccountInfo<'a>,
+    /// authority_signer_seeds
+    pub authority_signer_seeds: Option<&'b [&'b [u8]]>,
+    /// token_program
+    pub token_program: AccountInfo<'a>,
+    /// Transfer hook accounts for `DrainMode::TransferTo`, see
+    /// `spl_token_transfer_checked_with_hook`.
+    pub additional_accounts: &'b [AccountInfo<'a>],
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_program::{program_option::COption, program_pack::Pack, pubkey::Pubkey};
+    use spl_token_2022::state::{AccountState, Mint};
+
+    use super::*;
+
+    #[test]
+    fn drain_checks_mint_and_skips_native_burn() {
+        let mint_key = spl_token_2022::native_mint::ID;
+        let owner_key = Pubkey::new_unique();
+        let account_key = Pubkey::new_unique();
+        let destination_key = Pubkey::new_unique();
+        let token_program_id = spl_token_2022::ID;
+        let system_program_id = Pubkey::default();
+        let mut lamports = [0; 5];
+        let [account_lamports, mint_lamports, owner_lamports, destination_lamports, program_lamports] =
+            &mut lamports;
+        let mut account_data = vec![0; Account::LEN];
+        Account {
+            mint: mint_key,
+            owner: owner_key,
+            amount: 1_000,
+            state: AccountState::Initialized,
+            is_native: COption::Some(2_039_280),
+            ..Default::default()
+        }
+        .pack_into_slice(&mut account_data);
+        let mut mint_data = vec![0; Mint::LEN];
+        Mint {
+            decimals: 9,
+            is_initialized: true,
+            ..Default::default()
+        }
+        .pack_into_slice(&mut mint_data);
+        let account = AccountInfo::new(
+            &account_key,
+            false,
+            true,
+            account_lamports,
+            &mut account_data,
+            &token_program_id,
+            false,
+        );
+        let mint = AccountInfo::new(
+            &mint_key,
+            false,
+            true,
+            mint_lamports,
+            &mut mint_data,
+            &token_program_id,
+            false,
+        );
+        let owner = AccountInfo::new(
+            &owner_key,
+            true,
+            false,
+            owner_lamports,
+            &mut [],
+            &system_program_id,
+            false,
+        );
+        let destination = AccountInfo::new(
+            &destination_key,
+            false,
+            true,
+            destination_lamports,
+            &mut [],
+            &system_program_id,
+            false,
+        );
+        let token_program = AccountInfo::new(
+            &token_program_id,
+            false,
+            false,
+            program_lamports,
+            &mut [],
+            &system_program_id,
+            true,
+        );
+        // The native balance is released as lamports by the close, nothing is burned.
+        assert_eq!(
+            drain_and_close_token_account(
+                DrainAndCloseTokenAccountParams {
+                    account: account.clone(),
+                    mint: mint.clone(),
+                    authority: owner.clone(),
+                    destination: destination.clone(),
+                    authority_signer_seeds: None,
+                    token_program: token_program.clone(),
+                    additional_accounts: &[],
+                },
+                DrainMode::Burn,
+            ),
+            Ok(0)
+        );
+        assert_eq!(
+            drain_and_close_token_account(
+                DrainAndCloseTokenAccountParams {
+                    account: account.clone(),
+                    mint: mint.clone(),
+                    authority: owner.clone(),
+                    destination: destination.clone(),
+                    authority_signer_seeds: None,
+                    token_program: token_program.clone(),
+                    additional_accounts: &[],
+                },
+                DrainMode::TransferTo(destination.clone()),
+            ),
+            Ok(1_000)
+        );
+
+        let other_mint_key = Pubkey::new_unique();
+        let mut other_mint_lamports = 0;
+        let mut other_mint_data = vec![0; Mint::LEN];
+        let other_mint = AccountInfo::new(
+            &other_mint_key,
+            false,
+            true,
+            &mut other_mint_lamports,
+            &mut other_mint_data,
+            &token_program_id,
+            false,
+        );
+        assert_eq!(
+            drain_and_close_token_account(
+                DrainAndCloseTokenAccountParams {
+                    account,
+                    mint: other_mint,
+                    authority: owner,
+                    destination,
+                    authority_signer_seeds: None,
+                    token_program,
+                    additional_accounts: &[],
+                },
+                DrainMode::Burn,
+            ),
+            Err(ProgramError::InvalidAccountData)
+        );
+    }
+}
@@ -1,7 +1,36 @@
 use solana_program::{
-    account_info::AccountInfo, entrypoint::ProgramResult, program::invoke_signed,
+    account_info::AccountInfo, entrypoint::ProgramResult, msg, program::invoke_signed,
+    program_error::ProgramError,
 };
+use spl_memo_interface::instruction::build_memo;
+use spl_token_2022::{error::TokenError, extension::transfer_fee, onchain};
+use spl_token_metadata_interface::{instruction as token_metadata_instruction, state::Field};
 
+use super::{
+    get_mint_decimals, get_mint_transfer_hook_program_id, get_owner_from_token_account,
+    is_cpi_guard_enabled, is_memo_transfer_required,
+};
+
+/// Token-2022 rejects owner-signed transfers and burns made through CPI while the
+/// source account has CPI Guard enabled; only a delegate may move the funds.
+fn assert_cpi_guard_allows_owner_authority(
+    source: &AccountInfo,
+    authority: &AccountInfo,
+    error: TokenError,
+) -> ProgramResult {
+    if is_cpi_guard_enabled(source)? && get_owner_from_token_account(source)? == *authority.key {
+        msg!(
+            "CPI Guard is enabled on {}, ask the owner to disable it",
+            source.key
+        );
+        return Err(error.into());
+    }
+
+    Ok(())
+}
+
+/// Returns `TokenError::CpiGuardBurnBlocked` if the owner signs for a source
+/// account with CPI Guard enabled.
 pub fn spl_token_burn(params: TokenBurnParams<'_, '_>) -> ProgramResult {
     let TokenBurnParams {
         mint,
@@ -11,6 +40,7 @@ pub fn spl_token_burn(params: TokenBurnParams<'_, '_>) -> ProgramResult {
         amount,
         authority_signer_seeds,
     } = params;
+    assert_cpi_guard_allows_owner_authority(&source, &authority, TokenError::CpiGuardBurnBlocked)?;
     let mut seeds: Vec<&[&[u8]]> = vec![];
     if let Some(seed) = authority_signer_seeds {
         seeds.push(seed);
@@ -45,6 +75,39 @@ pub struct TokenBurnParams<'a: 'b, 'b> {
     pub token_program: AccountInfo<'a>,
 }
 
+/// Same as `spl_token_burn`, but uses `burn_checked` with the decimals read from `mint`.
+pub fn spl_token_burn_checked(params: TokenBurnParams<'_, '_>) -> ProgramResult {
+    let TokenBurnParams {
+        mint,
+        source,
+        authority,
+        token_program,
+        amount,
+        authority_signer_seeds,
+    } = params;
+    assert_cpi_guard_allows_owner_authority(&source, &authority, TokenError::CpiGuardBurnBlocked)?;
+    let decimals = get_mint_decimals(&mint)?;
+    let mut seeds: Vec<&[&[u8]]> = vec![];
+    if let Some(seed) = authority_signer_seeds {
+        seeds.push(seed);
+    }
+    invoke_signed(
+        &spl_token_2022::instruction::burn_checked(
+            token_program.key,
+            source.key,
+            mint.key,
+            authority.key,
+            &[],
+            amount,
+            decimals,
+        )?,
+        &[source, mint, authority, token_program],
+        seeds.as_slice(),
+    )
+}
+
+/// Returns `TokenError::CpiGuardCloseAccountBlocked` if the account has CPI Guard
+/// enabled and `destination` is not the account owner.
 pub fn spl_token_close(params: TokenCloseParams<'_, '_>) -> ProgramResult {
     let TokenCloseParams {
         account,
@@ -53,6 +116,15 @@ pub fn spl_token_close(params: TokenCloseParams<'_, '_>) -> ProgramResult {
         authority_signer_seeds,
         token_program,
     } = params;
+    if is_cpi_guard_enabled(&account)?
+        && get_owner_from_token_account(&account)? != *destination.key
+    {
+        msg!(
+            "CPI Guard is enabled on {}, ask the owner to disable it",
+            account.key
+        );
+        return Err(TokenError::CpiGuardCloseAccountBlocked.into());
+    }
     let mut seeds: Vec<&[&[u8]]> = vec![];
     if let Some(seed) = authority_signer_seeds {
         seeds.push(seed);
@@ -127,6 +199,38 @@ pub struct TokenMintToParams<'a: 'b, 'b> {
     pub token_program: AccountInfo<'a>,
 }
 
+/// Same as `spl_token_mint_to`, but uses `mint_to_checked` with the decimals read from `mint`.
+pub fn spl_token_mint_to_checked(params: TokenMintToParams<'_, '_>) -> ProgramResult {
+    let TokenMintToParams {
+        mint,
+        destination,
+        authority,
+        token_program,
+        amount,
+        authority_signer_seeds,
+    } = params;
+    let decimals = get_mint_decimals(&mint)?;
+    let mut seeds: Vec<&[&[u8]]> = vec![];
+    if let Some(seed) = authority_signer_seeds {
+        seeds.push(seed);
+    }
+    invoke_signed(
+        &spl_token_2022::instruction::mint_to_checked(
+            token_program.key,
+            mint.key,
+            destination.key,
+            authority.key,
+            &[],
+            amount,
+            decimals,
+        )?,
+        &[mint, destination, authority, token_program],
+        seeds.as_slice(),
+    )
+}
+
+/// Returns `TokenError::CpiGuardTransferBlocked` if the owner signs for a source
+/// account with CPI Guard enabled.
 #[allow(deprecated)]
 pub fn spl_token_transfer(params: TokenTransferParams<'_, '_>) -> ProgramResult {
     let TokenTransferParams {
@@ -138,6 +242,11 @@ pub fn spl_token_transfer(params: TokenTransferParams<'_, '_>) -> ProgramResult
         authority_signer_seeds,
         ..
     } = params;
+    assert_cpi_guard_allows_owner_authority(
+        &source,
+        &authority,
+        TokenError::CpiGuardTransferBlocked,
+    )?;
     let seeds = if let Some(seeds) = authority_signer_seeds {
         seeds
     } else {
@@ -177,6 +286,8 @@ pub struct TokenTransferParams<'a: 'b, 'b> {
     pub token_program: AccountInfo<'a>,
 }
 
+/// Returns `TokenError::CpiGuardTransferBlocked` if the owner signs for a source
+/// account with CPI Guard enabled.
 pub fn spl_token_transfer_checked(params: TokenTransferCheckedParams<'_, '_>) -> ProgramResult {
     let TokenTransferCheckedParams {
         mint,
@@ -188,6 +299,11 @@ pub fn spl_token_transfer_checked(params: TokenTransferCheckedParams<'_, '_>) ->
         authority_signer_seeds,
         decimals,
     } = params;
+    assert_cpi_guard_allows_owner_authority(
+        &source,
+        &authority,
+        TokenError::CpiGuardTransferBlocked,
+    )?;
     let seeds = if let Some(seeds) = authority_signer_seeds {
         seeds
     } else {
@@ -230,3 +346,650 @@ pub struct TokenTransferCheckedParams<'a: 'b, 'b> {
     /// decimals
     pub decimals: u8,
 }
+
+/// Same as `spl_token_transfer_checked`, but reads the decimals from `mint`
+/// instead of trusting the caller. Takes the same params as `spl_token_transfer`.
+pub fn spl_token_transfer_checked_from_mint(params: TokenTransferParams<'_, '_>) -> ProgramResult {
+    let TokenTransferParams {
+        mint,
+        source,
+        destination,
+        amount,
+        authority,
+        authority_signer_seeds,
+        token_program,
+    } = params;
+    let decimals = get_mint_decimals(&mint)?;
+
+    spl_token_transfer_checked(TokenTransferCheckedParams {
+        mint,
+        source,
+        destination,
+        amount,
+        authority,
+        authority_signer_seeds,
+        token_program,
+        decimals,
+    })
+}
+
+/// Same as `spl_token_transfer_checked`, including the CPI Guard check, but if
+/// the mint has the TransferHook extension, the hook program, its
+/// extra-account-metas PDA and the resolved extra accounts are looked up in
+/// `additional_accounts` and appended to the CPI.
+pub fn spl_token_transfer_checked_with_hook(
+    params: TokenTransferCheckedWithHookParams<'_, '_>,
+) -> ProgramResult {
+    let TokenTransferCheckedWithHookParams {
+        mint,
+        source,
+        destination,
+        amount,
+        authority,
+        token_program,
+        authority_signer_seeds,
+        decimals,
+        additional_accounts,
+    } = params;
+
+    if get_mint_transfer_hook_program_id(&mint)?.is_none() {
+        return spl_token_transfer_checked(TokenTransferCheckedParams {
+            mint,
+            source,
+            destination,
+            amount,
+            authority,
+            authority_signer_seeds,
+            token_program,
+            decimals,
+        });
+    }
+
+    assert_cpi_guard_allows_owner_authority(
+        &source,
+        &authority,
+        TokenError::CpiGuardTransferBlocked,
+    )?;
+    let seeds = if let Some(seeds) = authority_signer_seeds {
+        seeds
+    } else {
+        &[]
+    };
+
+    onchain::invoke_transfer_checked(
+        token_program.key,
+        source,
+        mint,
+        destination,
+        authority,
+        additional_accounts,
+        amount,
+        decimals,
+        &[seeds],
+    )
+}
+
+/// TokenTransferCheckedWithHookParams
+#[derive(Debug)]
+pub struct TokenTransferCheckedWithHookParams<'a: 'b, 'b> {
+    /// mint
+    pub mint: AccountInfo<'a>,
+    /// source
+    pub source: AccountInfo<'a>,
+    /// destination
+    pub destination: AccountInfo<'a>,
+    /// amount
+    pub amount: u64,
+    /// authority
+    pub authority: AccountInfo<'a>,
+    /// authority_signer_seeds
+    pub authority_signer_seeds: Option<&'b [&'b [u8]]>,
+    /// token_program
+    pub token_program: AccountInfo<'a>,
+    /// decimals
+    pub decimals: u8,
+    /// Remaining accounts holding the hook program, its extra-account-metas
+    /// PDA and every extra account it resolves to.
+    pub additional_accounts: &'b [AccountInfo<'a>],
+}
+
+pub fn spl_token_transfer_checked_with_fee(
+    params: TokenTransferCheckedWithFeeParams<'_, '_>,
+) -> ProgramResult {
+    let TokenTransferCheckedWithFeeParams {
+        mint,
+        source,
+        destination,
+        amount,
+        authority,
+        token_program,
+        authority_signer_seeds,
+        decimals,
+        fee,
+    } = params;
+    assert_cpi_guard_allows_owner_authority(
+        &source,
+        &authority,
+        TokenError::CpiGuardTransferBlocked,
+    )?;
+    let seeds = if let Some(seeds) = authority_signer_seeds {
+        seeds
+    } else {
+        &[]
+    };
+
+    invoke_signed(
+        &transfer_fee::instruction::transfer_checked_with_fee(
+            token_program.key,
+            source.key,
+            mint.key,
+            destination.key,
+            authority.key,
+            &[],
+            amount,
+            decimals,
+            fee,
+        )?,
+        &[source, mint, destination, authority, token_program],
+        &[seeds],
+    )
+}
+
+/// TokenTransferCheckedWithFeeParams
+#[derive(Debug)]
+pub struct TokenTransferCheckedWithFeeParams<'a: 'b, 'b> {
+    /// mint
+    pub mint: AccountInfo<'a>,
+    /// source
+    pub source: AccountInfo<'a>,
+    /// destination
+    pub destination: AccountInfo<'a>,
+    /// amount
+    pub amount: u64,
+    /// authority
+    pub authority: AccountInfo<'a>,
+    /// authority_signer_seeds
+    pub authority_signer_seeds: Option<&'b [&'b [u8]]>,
+    /// token_program
+    pub token_program: AccountInfo<'a>,
+    /// decimals
+    pub decimals: u8,
+    /// Expected fee, must match the fee the mint charges for `amount`.
+    pub fee: u64,
+}
+
+pub fn spl_token_withdraw_withheld_tokens_from_accounts(
+    params: TokenWithdrawWithheldTokensFromAccountsParams<'_, '_>,
+) -> ProgramResult {
+    let TokenWithdrawWithheldTokensFromAccountsParams {
+        mint,
+        destination,
+        authority,
+        sources,
+        authority_signer_seeds,
+        token_program,
+    } = params;
+    let mut seeds: Vec<&[&[u8]]> = vec![];
+    if let Some(seed) = authority_signer_seeds {
+        seeds.push(seed);
+    }
+    let source_keys: Vec<_> = sources.iter().map(|source| source.key).collect();
+    let instruction = transfer_fee::instruction::withdraw_withheld_tokens_from_accounts(
+        token_program.key,
+        mint.key,
+        destination.key,
+        authority.key,
+        &[],
+        &source_keys,
+    )?;
+    let mut account_infos = vec![mint, destination, authority, token_program];
+    account_infos.extend_from_slice(sources);
+
+    invoke_signed(&instruction, &account_infos, seeds.as_slice())
+}
+
+/// TokenWithdrawWithheldTokensFromAccountsParams
+pub struct TokenWithdrawWithheldTokensFromAccountsParams<'a: 'b, 'b> {
+    /// mint
+    pub mint: AccountInfo<'a>,
+    /// Token account receiving the withheld fees.
+    pub destination: AccountInfo<'a>,
+    /// Withdraw withheld authority of the mint.
+    pub authority: AccountInfo<'a>,
+    /// Token accounts to withdraw withheld fees from.
+    pub sources: &'b [AccountInfo<'a>],
+    /// authority_signer_seeds
+    pub authority_signer_seeds: Option<&'b [&'b [u8]]>,
+    /// token_program
+    pub token_program: AccountInfo<'a>,
+}
+
+pub fn spl_token_harvest_withheld_tokens_to_mint(
+    params: TokenHarvestWithheldTokensToMintParams<'_, '_>,
+) -> ProgramResult {
+    let TokenHarvestWithheldTokensToMintParams {
+        mint,
+        sources,
+        token_program,
+    } = params;
+    let source_keys: Vec<_> = sources.iter().map(|source| source.key).collect();
+    let instruction = transfer_fee::instruction::harvest_withheld_tokens_to_mint(
+        token_program.key,
+        mint.key,
+        &source_keys,
+    )?;
+    let mut account_infos = vec![mint, token_program];
+    account_infos.extend_from_slice(sources);
+
+    invoke_signed(&instruction, &account_infos, &[])
+}
+
+/// TokenHarvestWithheldTokensToMintParams
+pub struct TokenHarvestWithheldTokensToMintParams<'a: 'b, 'b> {
+    /// mint
+    pub mint: AccountInfo<'a>,
+    /// Token accounts to harvest withheld fees from.
+    pub sources: &'b [AccountInfo<'a>],
+    /// token_program
+    pub token_program: AccountInfo<'a>,
+}
+
+/// Initializes token metadata. For metadata stored on a Token-2022 mint the mint
+/// must already hold enough lamports for the grown account to stay rent-exempt.
+pub fn spl_token_metadata_initialize(
+    params: TokenMetadataInitializeParams<'_, '_>,
+) -> ProgramResult {
+    let TokenMetadataInitializeParams {
+        metadata,
+        update_authority,
+        mint,
+        mint_authority,
+        name,
+        symbol,
+        uri,
+        authority_signer_seeds,
+        program,
+    } = params;
+    let mut seeds: Vec<&[&[u8]]> = vec![];
+    if let Some(seed) = authority_signer_seeds {
+        seeds.push(seed);
+    }
+    invoke_signed(
+        &token_metadata_instruction::initialize(
+            program.key,
+            metadata.key,
+            update_authority.key,
+            mint.key,
+            mint_authority.key,
+            name,
+            symbol,
+            uri,
+        ),
+        &[metadata, update_authority, mint, mint_authority, program],
+        seeds.as_slice(),
+    )
+}
+
+/// TokenMetadataInitializeParams
+pub struct TokenMetadataInitializeParams<'a: 'b, 'b> {
+    /// Account holding the metadata, the mint itself for Token-2022 on-mint metadata.
+    pub metadata: AccountInfo<'a>,
+    /// update_authority
+    pub update_authority: AccountInfo<'a>,
+    /// mint
+    pub mint: AccountInfo<'a>,
+    /// mint_authority
+    pub mint_authority: AccountInfo<'a>,
+    /// name
+    pub name: String,
+    /// symbol
+    pub symbol: String,
+    /// uri
+    pub uri: String,
+    /// Signer seeds of the mint authority.
+    pub authority_signer_seeds: Option<&'b [&'b [u8]]>,
+    /// Program implementing the token-metadata interface.
+    pub program: AccountInfo<'a>,
+}
+
+pub fn spl_token_metadata_update_field(
+    params: TokenMetadataUpdateFieldParams<'_, '_>,
+) -> ProgramResult {
+    let TokenMetadataUpdateFieldParams {
+        metadata,
+        update_authority,
+        field,
+        value,
+        authority_signer_seeds,
+        program,
+    } = params;
+    let mut seeds: Vec<&[&[u8]]> = vec![];
+    if let Some(seed) = authority_signer_seeds {
+        seeds.push(seed);
+    }
+    invoke_signed(
+        &token_metadata_instruction::update_field(
+            program.key,
+            metadata.key,
+            update_authority.key,
+            field,
+            value,
+        ),
+        &[metadata, update_authority, program],
+        seeds.as_slice(),
+    )
+}
+
+/// TokenMetadataUpdateFieldParams
+pub struct TokenMetadataUpdateFieldParams<'a: 'b, 'b> {
+    /// metadata
+    pub metadata: AccountInfo<'a>,
+    /// update_authority
+    pub update_authority: AccountInfo<'a>,
+    /// Field to update, `Field::Key` adds or replaces an additional metadata entry.
+    pub field: Field,
+    /// value
+    pub value: String,
+    /// authority_signer_seeds
+    pub authority_signer_seeds: Option<&'b [&'b [u8]]>,
+    /// Program implementing the token-metadata interface.
+    pub program: AccountInfo<'a>,
+}
+
+pub fn spl_token_metadata_remove_key(
+    params: TokenMetadataRemoveKeyParams<'_, '_>,
+) -> ProgramResult {
+    let TokenMetadataRemoveKeyParams {
+        metadata,
+        update_authority,
+        key,
+        idempotent,
+        authority_signer_seeds,
+        program,
+    } = params;
+    let mut seeds: Vec<&[&[u8]]> = vec![];
+    if let Some(seed) = authority_signer_seeds {
+        seeds.push(seed);
+    }
+    invoke_signed(
+        &token_metadata_instruction::remove_key(
+            program.key,
+            metadata.key,
+            update_authority.key,
+            key,
+            idempotent,
+        ),
+        &[metadata, update_authority, program],
+        seeds.as_slice(),
+    )
+}
+
+/// TokenMetadataRemoveKeyParams
+pub struct TokenMetadataRemoveKeyParams<'a: 'b, 'b> {
+    /// metadata
+    pub metadata: AccountInfo<'a>,
+    /// update_authority
+    pub update_authority: AccountInfo<'a>,
+    /// Additional metadata key to remove.
+    pub key: String,
+    /// If true, removing a missing key is not an error.
+    pub idempotent: bool,
+    /// authority_signer_seeds
+    pub authority_signer_seeds: Option<&'b [&'b [u8]]>,
+    /// Program implementing the token-metadata interface.
+    pub program: AccountInfo<'a>,
+}
+
+pub fn spl_memo(params: MemoParams<'_, '_>) -> ProgramResult {
+    let MemoParams {
+        memo,
+        signers,
+        signer_seeds,
+        memo_program,
+    } = params;
+    if *memo_program.key != spl_memo_interface::v3::id()
+        && *memo_program.key != spl_memo_interface::v1::id()
+    {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    let mut seeds: Vec<&[&[u8]]> = vec![];
+    if let Some(seed) = signer_seeds {
+        seeds.push(seed);
+    }
+    let signer_keys: Vec<_> = signers.iter().map(|signer| signer.key).collect();
+    let instruction = build_memo(memo_program.key, memo.as_bytes(), &signer_keys);
+    let mut account_infos = signers.to_vec();
+    account_infos.push(memo_program);
+
+    invoke_signed(&instruction, &account_infos, seeds.as_slice())
+}
+
+/// MemoParams
+pub struct MemoParams<'a: 'b, 'b> {
+    /// memo
+    pub memo: &'b str,
+    /// Accounts that must sign the memo, may be empty.
+    pub signers: &'b [AccountInfo<'a>],
+    /// signer_seeds
+    pub signer_seeds: Option<&'b [&'b [u8]]>,
+    /// memo_program
+    pub memo_program: AccountInfo<'a>,
+}
+
+/// Same as `spl_token_transfer_checked`, but if the destination requires incoming
+/// transfer memos, the memo is logged with the SPL Memo program right before the transfer.
+pub fn spl_token_transfer_checked_with_memo(
+    params: TokenTransferCheckedWithMemoParams<'_, '_>,
+) -> ProgramResult {
+    let TokenTransferCheckedWithMemoParams {
+        mint,
+        source,
+        destination,
+        amount,
+        authority,
+        token_program,
+        authority_signer_seeds,
+        decimals,
+        memo,
+        memo_program,
+    } = params;
+
+    if is_memo_transfer_required(&destination)? {
+        spl_memo(MemoParams {
+            memo,
+            signers: &[],
+            signer_seeds: None,
+            memo_program,
+        })?;
+    }
+
+    spl_token_transfer_checked(TokenTransferCheckedParams {
+        mint,
+        source,
+        destination,
+        amount,
+        authority,
+        authority_signer_seeds,
+        token_program,
+        decimals,
+    })
+}
+
+/// TokenTransferCheckedWithMemoParams
+#[derive(Debug)]
+pub struct TokenTransferCheckedWithMemoParams<'a: 'b, 'b> {
+    /// mint
+    pub mint: AccountInfo<'a>,
+    /// source
+    pub source: AccountInfo<'a>,
+    /// destination
+    pub destination: AccountInfo<'a>,
+    /// amount
+    pub amount: u64,
+    /// authority
+    pub authority: AccountInfo<'a>,
+    /// authority_signer_seeds
+    pub authority_signer_seeds: Option<&'b [&'b [u8]]>,
+    /// token_program
+    pub token_program: AccountInfo<'a>,
+    /// decimals
+    pub decimals: u8,
+    /// Memo sent only if the destination requires one.
+    pub memo: &'b str,
+    /// memo_program
+    pub memo_program: AccountInfo<'a>,
+}
+
+/// Approves `delegate` for `amount` with `approve_checked`, reading the decimals
+/// from `mint`. Returns `TokenError::CpiGuardApproveBlocked` if the source
+/// account has CPI Guard enabled, as Token-2022 rejects approvals made through CPI.
+pub fn spl_token_approve_checked(params: TokenApproveParams<'_, '_>) -> ProgramResult {
+    let TokenApproveParams {
+        mint,
+        source,
+        delegate,
+        amount,
+        owner,
+        owner_signer_seeds,
+        token_program,
+    } = params;
+    if is_cpi_guard_enabled(&source)? {
+        msg!(
+            "CPI Guard is enabled on {}, ask the owner to disable it",
+            source.key
+        );
+        return Err(TokenError::CpiGuardApproveBlocked.into());
+    }
+    let decimals = get_mint_decimals(&mint)?;
+    let mut seeds: Vec<&[&[u8]]> = vec![];
+    if let Some(seed) = owner_signer_seeds {
+        seeds.push(seed);
+    }
+    invoke_signed(
+        &spl_token_2022::instruction::approve_checked(
+            token_program.key,
+            source.key,
+            mint.key,
+            delegate.key,
+            owner.key,
+            &[],
+            amount,
+            decimals,
+        )?,
+        &[source, mint, delegate, owner, token_program],
+        seeds.as_slice(),
+    )
+}
+
+/// TokenApproveParams
+pub struct TokenApproveParams<'a: 'b, 'b> {
+    /// mint
+    pub mint: AccountInfo<'a>,
+    /// source
+    pub source: AccountInfo<'a>,
+    /// delegate
+    pub delegate: AccountInfo<'a>,
+    /// amount
+    pub amount: u64,
+    /// Owner of the source account.
+    pub owner: AccountInfo<'a>,
+    /// owner_signer_seeds
+    pub owner_signer_seeds: Option<&'b [&'b [u8]]>,
+    /// token_program
+    pub token_program: AccountInfo<'a>,
+}
+
+pub fn spl_token_revoke(params: TokenRevokeParams<'_, '_>) -> ProgramResult {
+    let TokenRevokeParams {
+        source,
+        owner,
+        owner_signer_seeds,
+        token_program,
+    } = params;
+    let mut seeds: Vec<&[&[u8]]> = vec![];
+    if let Some(seed) = owner_signer_seeds {
+        seeds.push(seed);
+    }
+    invoke_signed(
+        &spl_token_2022::instruction::revoke(token_program.key, source.key, owner.key, &[])?,
+        &[source, owner, token_program],
+        seeds.as_slice(),
+    )
+}
+
+/// TokenRevokeParams
+pub struct TokenRevokeParams<'a: 'b, 'b> {
+    /// source
+    pub source: AccountInfo<'a>,
+    /// Owner of the source account.
+    pub owner: AccountInfo<'a>,
+    /// owner_signer_seeds
+    pub owner_signer_seeds: Option<&'b [&'b [u8]]>,
+    /// token_program
+    pub token_program: AccountInfo<'a>,
+}
+
+pub fn spl_token_freeze_account(params: TokenFreezeAccountParams<'_, '_>) -> ProgramResult {
+    let TokenFreezeAccountParams {
+        account,
+        mint,
+        freeze_authority,
+        freeze_authority_signer_seeds,
+        token_program,
+    } = params;
+    let mut seeds: Vec<&[&[u8]]> = vec![];
+    if let Some(seed) = freeze_authority_signer_seeds {
+        seeds.push(seed);
+    }
+    invoke_signed(
+        &spl_token_2022::instruction::freeze_account(
+            token_program.key,
+            account.key,
+            mint.key,
+            freeze_authority.key,
+            &[],
+        )?,
+        &[account, mint, freeze_authority, token_program],
+        seeds.as_slice(),
+    )
+}
+
+/// Takes the same params as `spl_token_freeze_account`.
+pub fn spl_token_thaw_account(params: TokenFreezeAccountParams<'_, '_>) -> ProgramResult {
+    let TokenFreezeAccountParams {
+        account,
+        mint,
+        freeze_authority,
+        freeze_authority_signer_seeds,
+        token_program,
+    } = params;
+    let mut seeds: Vec<&[&[u8]]> = vec![];
+    if let Some(seed) = freeze_authority_signer_seeds {
+        seeds.push(seed);
+    }
+    invoke_signed(
+        &spl_token_2022::instruction::thaw_account(
+            token_program.key,
+            account.key,
+            mint.key,
+            freeze_authority.key,
+            &[],
+        )?,
+        &[account, mint, freeze_authority, token_program],
+        seeds.as_slice(),
+    )
+}
+
+/// TokenFreezeAccountParams
+pub struct TokenFreezeAccountParams<'a: 'b, 'b> {
+    /// Token account to freeze or thaw.
+    pub account: AccountInfo<'a>,
+    /// mint
+    pub mint: AccountInfo<'a>,
+    /// Freeze authority of the mint.
+    pub freeze_authority: AccountInfo<'a>,
+    /// freeze_authority_signer_seeds
+    pub freeze_authority_signer_seeds: Option<&'b [&'b [u8]]>,
+    /// token_program
+    pub token_program: AccountInfo<'a>,
+}
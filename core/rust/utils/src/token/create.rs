@@ -0,0 +1,645 @@
+use solana_program::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    instruction::Instruction,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    sysvar::Sysvar,
+};
+use solana_system_interface::instruction as system_instruction;
+use spl_token_2022::{
+    extension::{
+        default_account_state, group_member_pointer, group_pointer, interest_bearing_mint,
+        memo_transfer, metadata_pointer, pausable, scaled_ui_amount, transfer_fee, transfer_hook,
+        BaseStateWithExtensions, ExtensionType,
+    },
+    state::{Account, AccountState, Mint},
+};
+use spl_token_metadata_interface::state::{Field, TokenMetadata};
+
+use super::{
+    spl_token_metadata_initialize, spl_token_metadata_update_field, unpack,
+    TokenMetadataInitializeParams, TokenMetadataUpdateFieldParams,
+};
+use crate::create_or_allocate_account_raw;
+
+/// Fixed-size mint extension, initialized before `initialize_mint2`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MintExtensionConfig {
+    TransferFeeConfig {
+        transfer_fee_config_authority: Option<Pubkey>,
+        withdraw_withheld_authority: Option<Pubkey>,
+        transfer_fee_basis_points: u16,
+        maximum_fee: u64,
+    },
+    MintCloseAuthority {
+        close_authority: Option<Pubkey>,
+    },
+    DefaultAccountState {
+        state: AccountState,
+    },
+    NonTransferable,
+    InterestBearingConfig {
+        rate_authority: Option<Pubkey>,
+        rate: i16,
+    },
+    PermanentDelegate {
+        delegate: Pubkey,
+    },
+    TransferHook {
+        authority: Option<Pubkey>,
+        program_id: Option<Pubkey>,
+    },
+    MetadataPointer {
+        authority: Option<Pubkey>,
+        metadata_address: Option<Pubkey>,
+    },
+    GroupPointer {
+        authority: Option<Pubkey>,
+        group_address: Option<Pubkey>,
+    },
+    GroupMemberPointer {
+        authority: Option<Pubkey>,
+        member_address: Option<Pubkey>,
+    },
+    ScaledUiAmount {
+        authority: Option<Pubkey>,
+        multiplier: f64,
+    },
+    Pausable {
+        authority: Pubkey,
+    },
+}
+
+impl MintExtensionConfig {
+    pub fn extension_type(&self) -> ExtensionType {
+        match self {
+            Self::TransferFeeConfig { .. } => ExtensionType::TransferFeeConfig,
+            Self::MintCloseAuthority { .. } => ExtensionType::MintCloseAuthority,
+            Self::DefaultAccountState { .. } => ExtensionType::DefaultAccountState,
+            Self::NonTransferable => ExtensionType::NonTransferable,
+            Self::InterestBearingConfig { .. } => ExtensionType::InterestBearingConfig,
+            Self::PermanentDelegate { .. } => ExtensionType::PermanentDelegate,
+            Self::TransferHook { .. } => ExtensionType::TransferHook,
+            Self::MetadataPointer { .. } => ExtensionType::MetadataPointer,
+            Self::GroupPointer { .. } => ExtensionType::GroupPointer,
+            Self::GroupMemberPointer { .. } => ExtensionType::GroupMemberPointer,
+            Self::ScaledUiAmount { .. } => ExtensionType::ScaledUiAmount,
+            Self::Pausable { .. } => ExtensionType::Pausable,
+        }
+    }
+
+    fn initialize_instruction(
+        &self,
+        token_program_id: &Pubkey,
+        mint: &Pubkey,
+    ) -> Result<Instruction, ProgramError> {
+        match *self {
+            Self::TransferFeeConfig {
+                transfer_fee_config_authority,
+                withdraw_withheld_authority,
+                transfer_fee_basis_points,
+                maximum_fee,
+            } => transfer_fee::instruction::initialize_transfer_fee_config(
+                token_program_id,
+                mint,
+                transfer_fee_config_authority.as_ref(),
+                withdraw_withheld_authority.as_ref(),
+                transfer_fee_basis_points,
+                maximum_fee,
+            ),
+            Self::MintCloseAuthority { close_authority } => {
+                spl_token_2022::instruction::initialize_mint_close_authority(
+                    token_program_id,
+                    mint,
+                    close_authority.as_ref(),
+                )
+            }
+            Self::DefaultAccountState { state } => {
+                default_account_state::instruction::initialize_default_account_state(
+                    token_program_id,
+                    mint,
+                    &state,
+                )
+            }
+            Self::NonTransferable => spl_token_2022::instruction::initialize_non_transferable_mint(
+                token_program_id,
+                mint,
+            ),
+            Self::InterestBearingConfig {
+                rate_authority,
+                rate,
+            } => interest_bearing_mint::instruction::initialize(
+                token_program_id,
+                mint,
+                rate_authority,
+                rate,
+            ),
+            Self::PermanentDelegate { delegate } => {
+                spl_token_2022::instruction::initialize_permanent_delegate(
+                    token_program_id,
+                    mint,
+                    &delegate,
+                )
+            }
+            Self::TransferHook {
+                authority,
+                program_id,
+            } => transfer_hook::instruction::initialize(
+                token_program_id,
+                mint,
+                authority,
+                program_id,
+            ),
+            Self::MetadataPointer {
+                authority,
+                metadata_address,
+            } => metadata_pointer::instruction::initialize(
+                token_program_id,
+                mint,
+                authority,
+                metadata_address,
+            ),
+            Self::GroupPointer {
+                authority,
+                group_address,
+            } => group_pointer::instruction::initialize(
+                token_program_id,
+                mint,
+                authority,
+                group_address,
+            ),
+            Self::GroupMemberPointer {
+                authority,
+                member_address,
+            } => group_member_pointer::instruction::initialize(
+                token_program_id,
+                mint,
+                authority,
+                member_address,
+            ),
+            Self::ScaledUiAmount {
+                authority,
+                multiplier,
+            } => scaled_ui_amount::instruction::initialize(
+                token_program_id,
+                mint,
+                authority,
+                multiplier,
+            ),
+            Self::Pausable { authority } => {
+                pausable::instruction::initialize(token_program_id, mint, &authority)
+            }
+        }
+    }
+}
+
+/// Token metadata stored on the mint itself. Requires a `MetadataPointer`
+/// extension pointing at the mint.
+pub struct TokenMetadataConfig<'a: 'b, 'b> {
+    /// update_authority
+    pub update_authority: AccountInfo<'a>,
+    /// Only needed to sign for `additional_metadata`.
+    pub update_authority_signer_seeds: Option<&'b [&'b [u8]]>,
+    /// name
+    pub name: String,
+    /// symbol
+    pub symbol: String,
+    /// uri
+    pub uri: String,
+    /// additional_metadata
+    pub additional_metadata: Vec<(String, String)>,
+}
+
+/// Returns the size of a mint with `extensions`, and the extra size the
+/// on-mint token metadata needs, which requires a `MetadataPointer` to the mint.
+fn get_mint_space(
+    mint: &Pubkey,
+    extensions: &[MintExtensionConfig],
+    token_metadata: Option<&TokenMetadataConfig>,
+) -> Result<(usize, usize), ProgramError> {
+    let extension_types: Vec<ExtensionType> = extensions
+        .iter()
+        .map(MintExtensionConfig::extension_type)
+        .collect();
+    let space = ExtensionType::try_calculate_account_len::<Mint>(&extension_types)?;
+
+    let metadata_space = match token_metadata {
+        Some(config) => {
+            let points_to_mint = extensions.iter().any(|extension| {
+                matches!(
+                    extension,
+                    MintExtensionConfig::MetadataPointer {
+                        metadata_address: Some(address),
+                        ..
+                    } if address == mint
+                )
+            });
+            if !points_to_mint {
+                return Err(ProgramError::InvalidArgument);
+            }
+
+            TokenMetadata {
+                update_authority: Some(*config.update_authority.key).try_into()?,
+                mint: *mint,
+                name: config.name.clone(),
+                symbol: config.symbol.clone(),
+                uri: config.uri.clone(),
+                additional_metadata: config.additional_metadata.clone(),
+            }
+            .tlv_size_of()?
+        }
+        None => 0,
+    };
+
+    Ok((space, metadata_space))
+}
+
+/// Creates a mint sized for the given extensions, initializes them in order,
+/// then the mint itself and finally the on-mint token metadata. Rent for the
+/// variable-length metadata is funded up front.
+pub fn create_mint_with_extensions(
+    params: CreateMintWithExtensionsParams<'_, '_>,
+) -> ProgramResult {
+    let CreateMintWithExtensionsParams {
+        mint,
+        payer,
+        mint_authority,
+        freeze_authority,
+        decimals,
+        extensions,
+        token_metadata,
+        mint_signer_seeds,
+        mint_authority_signer_seeds,
+        system_program,
+        token_program,
+    } = params;
+
+    let (space, metadata_space) = get_mint_space(mint.key, extensions, token_metadata.as_ref())?;
+
+    // Token-2022 reallocs the mint when writing metadata, so fund the final size now.
+    let required_lamports = Rent::get()?
+        .minimum_balance(space + metadata_space)
+        .saturating_sub(mint.lamports());
+    if required_lamports > 0 {
+        invoke(
+            &system_instruction::transfer(payer.key, mint.key, required_lamports),
+            &[payer.clone(), mint.clone(), system_program.clone()],
+        )?;
+    }
+
+    create_or_allocate_account_raw(
+        *token_program.key,
+        &mint,
+        &system_program,
+        &payer,
+        space,
+        mint_signer_seeds.unwrap_or(&[]),
+    )?;
+
+    for extension in extensions {
+        invoke(
+            &extension.initialize_instruction(token_program.key, mint.key)?,
+            &[mint.clone(), token_program.clone()],
+        )?;
+    }
+
+    invoke(
+        &spl_token_2022::instruction::initialize_mint2(
+            token_program.key,
+            mint.key,
+            mint_authority.key,
+            freeze_authority.as_ref(),
+            decimals,
+        )?,
+        &[mint.clone(), token_program.clone()],
+    )?;
+
+    if let Some(config) = token_metadata {
+        let TokenMetadataConfig {
+            update_authority,
+            update_authority_signer_seeds,
+            name,
+            symbol,
+            uri,
+            additional_metadata,
+        } = config;
+
+        spl_token_metadata_initialize(TokenMetadataInitializeParams {
+            metadata: mint.clone(),
+            update_authority: update_authority.clone(),
+            mint: mint.clone(),
+            mint_authority,
+            name,
+            symbol,
+            uri,
+            authority_signer_seeds: mint_authority_signer_seeds,
+            program: token_program.clone(),
+        })?;
+
+        for (key, value) in additional_metadata {
+            spl_token_metadata_update_field(TokenMetadataUpdateFieldParams {
+                metadata: mint.clone(),
+                update_authority: update_authority.clone(),
+                field: Field::Key(key),
+                value,
+                authority_signer_seeds: update_authority_signer_seeds,
+                program: token_program.clone(),
+            })?;
+        }
+    }
+
+    Ok(())
+}
+
+/// CreateMintWithExtensionsParams
+pub struct CreateMintWithExtensionsParams<'a: 'b, 'b> {
+    /// Uninitialized mint account, must sign either directly or via `mint_signer_seeds`.
+    pub mint: AccountInfo<'a>,
+    /// payer
+    pub payer: AccountInfo<'a>,
+    /// mint_authority
+    pub mint_authority: AccountInfo<'a>,
+    /// freeze_authority
+    pub freeze_authority: Option<Pubkey>,
+    /// decimals
+    pub decimals: u8,
+    /// extensions
+    pub extensions: &'b [MintExtensionConfig],
+    /// token_metadata
+    pub token_metadata: Option<TokenMetadataConfig<'a, 'b>>,
+    /// mint_signer_seeds
+    pub mint_signer_seeds: Option<&'b [&'b [u8]]>,
+    /// Only needed to sign for `token_metadata`.
+    pub mint_authority_signer_seeds: Option<&'b [&'b [u8]]>,
+    /// system_program
+    pub system_program: AccountInfo<'a>,
+    /// token_program
+    pub token_program: AccountInfo<'a>,
+}
+
+/// Token account extension chosen by the account creator. Extensions the mint
+/// requires on its accounts are added automatically. CPI Guard can only be
+/// enabled by the owner in a top-level instruction, so it isn't offered here.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AccountExtensionConfig {
+    ImmutableOwner,
+    /// Enables `require_incoming_transfer_memos`, signed by the owner.
+    MemoTransfer,
+}
+
+impl AccountExtensionConfig {
+    pub fn extension_type(&self) -> ExtensionType {
+        match self {
+            Self::ImmutableOwner => ExtensionType::ImmutableOwner,
+            Self::MemoTransfer => ExtensionType::MemoTransfer,
+        }
+    }
+}
+
+/// Returns the size of a token account with `extensions` plus the ones the
+/// mint requires on its accounts.
+fn get_token_account_space(
+    mint_data: &[u8],
+    extensions: &[AccountExtensionConfig],
+) -> Result<usize, ProgramError> {
+    let mint_extension_types = unpack::<Mint>(mint_data)?.get_extension_types()?;
+    let mut extension_types =
+        ExtensionType::get_required_init_account_extensions(&mint_extension_types);
+    for extension in extensions {
+        let extension_type = extension.extension_type();
+        if !extension_types.contains(&extension_type) {
+            extension_types.push(extension_type);
+        }
+    }
+
+    ExtensionType::try_calculate_account_len::<Account>(&extension_types)
+}
+
+/// Creates a token account sized for the requested extensions plus the ones
+/// required by the mint, then initializes it with `initialize_account3`.
+pub fn create_token_account_with_extensions(
+    params: CreateTokenAccountWithExtensionsParams<'_, '_>,
+) -> ProgramResult {
+    let CreateTokenAccountWithExtensionsParams {
+        account,
+        payer,
+        mint,
+        owner,
+        extensions,
+        account_signer_seeds,
+        owner_signer_seeds,
+        system_program,
+        token_program,
+    } = params;
+
+    let space = get_token_account_space(&mint.try_borrow_data()?, extensions)?;
+
+    create_or_allocate_account_raw(
+        *token_program.key,
+        &account,
+        &system_program,
+        &payer,
+        space,
+        account_signer_seeds.unwrap_or(&[]),
+    )?;
+
+    if extensions.contains(&AccountExtensionConfig::ImmutableOwner) {
+        invoke(
+            &spl_token_2022::instruction::initialize_immutable_owner(
+                token_program.key,
+                account.key,
+            )?,
+            &[account.clone(), token_program.clone()],
+        )?;
+    }
+
+    invoke(
+        &spl_token_2022::instruction::initialize_account3(
+            token_program.key,
+            account.key,
+            mint.key,
+            owner.key,
+        )?,
+        &[account.clone(), mint.clone(), token_program.clone()],
+    )?;
+
+    let mut seeds: Vec<&[&[u8]]> = vec![];
+    if let Some(seed) = owner_signer_seeds {
+        seeds.push(seed);
+    }
+    if extensions.contains(&AccountExtensionConfig::MemoTransfer) {
+        invoke_signed(
+            &memo_transfer::instruction::enable_required_transfer_memos(
+                token_program.key,
+                account.key,
+                owner.key,
+                &[],
+            )?,
+            &[account.clone(), owner.clone(), token_program.clone()],
+            seeds.as_slice(),
+        )?;
+    }
+
+    Ok(())
+}
+
+/// CreateTokenAccountWithExtensionsParams
+pub struct CreateTokenAccountWithExtensionsParams<'a: 'b, 'b> {
+    /// Uninitialized token account, must sign either directly or via `account_signer_seeds`.
+    pub account: AccountInfo<'a>,
+    /// payer
+    pub payer: AccountInfo<'a>,
+    /// mint
+    pub mint: AccountInfo<'a>,
+    /// owner
+    pub owner: AccountInfo<'a>,
+    /// extensions
+    pub extensions: &'b [AccountExtensionConfig],
+    /// account_signer_seeds
+    pub account_signer_seeds: Option<&'b [&'b [u8]]>,
+    /// Only needed to sign for `MemoTransfer`.
+    pub owner_signer_seeds: Option<&'b [&'b [u8]]>,
+    /// system_program
+    pub system_program: AccountInfo<'a>,
+    /// token_program
+    pub token_program: AccountInfo<'a>,
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_program::program_pack::Pack;
+    use spl_token_2022::extension::{
+        non_transferable::NonTransferable, transfer_fee::TransferFeeConfig,
+        BaseStateWithExtensionsMut, StateWithExtensionsMut,
+    };
+
+    use super::*;
+
+    fn metadata_pointer(metadata_address: Pubkey) -> MintExtensionConfig {
+        MintExtensionConfig::MetadataPointer {
+            authority: None,
+            metadata_address: Some(metadata_address),
+        }
+    }
+
+    #[test]
+    fn mint_space_funds_token_metadata() {
+        let mint = Pubkey::new_unique();
+        let update_authority = Pubkey::new_unique();
+        let mut lamports = 0;
+        let mut data = [];
+        let token_metadata = TokenMetadataConfig {
+            update_authority: AccountInfo::new(
+                &update_authority,
+                true,
+                false,
+                &mut lamports,
+                &mut data,
+                &update_authority,
+                false,
+            ),
+            update_authority_signer_seeds: None,
+            name: "Name".to_string(),
+            symbol: "SYM".to_string(),
+            uri: "https://x".to_string(),
+            additional_metadata: vec![("a".to_string(), "1".to_string())],
+        };
+
+        // 165 byte base, account type, then the 4 + 64 byte MetadataPointer entry.
+        assert_eq!(
+            get_mint_space(&mint, &[metadata_pointer(mint)], None).unwrap(),
+            (234, 0)
+        );
+        // 12 byte TLV header, both keys, the three strings and one key/value pair.
+        assert_eq!(
+            get_mint_space(&mint, &[metadata_pointer(mint)], Some(&token_metadata)).unwrap(),
+            (234, 118)
+        );
+
+        let extensions = [
+            MintExtensionConfig::TransferFeeConfig {
+                transfer_fee_config_authority: None,
+                withdraw_withheld_authority: None,
+                transfer_fee_basis_points: 100,
+                maximum_fee: 1,
+            },
+            metadata_pointer(mint),
+        ];
+        let (space, metadata_space) =
+            get_mint_space(&mint, &extensions, Some(&token_metadata)).unwrap();
+        assert_eq!(
+            space,
+            ExtensionType::try_calculate_account_len::<Mint>(&[
+                ExtensionType::TransferFeeConfig,
+                ExtensionType::MetadataPointer,
+            ])
+            .unwrap()
+        );
+        assert_eq!(metadata_space, 118);
+
+        assert_eq!(
+            get_mint_space(&mint, &[], Some(&token_metadata)),
+            Err(ProgramError::InvalidArgument)
+        );
+        assert_eq!(
+            get_mint_space(
+                &mint,
+                &[metadata_pointer(Pubkey::new_unique())],
+                Some(&token_metadata)
+            ),
+            Err(ProgramError::InvalidArgument)
+        );
+    }
+
+    #[test]
+    fn token_account_space_adds_mint_required_extensions() {
+        let space = ExtensionType::try_calculate_account_len::<Mint>(&[
+            ExtensionType::TransferFeeConfig,
+            ExtensionType::NonTransferable,
+        ])
+        .unwrap();
+        let mut mint_data = vec![0; space];
+        let mut mint =
+            StateWithExtensionsMut::<Mint>::unpack_uninitialized(&mut mint_data).unwrap();
+        mint.base = Mint {
+            decimals: 0,
+            is_initialized: true,
+            ..Default::default()
+        };
+        mint.pack_base();
+        mint.init_account_type().unwrap();
+        mint.init_extension::<TransferFeeConfig>(true).unwrap();
+        mint.init_extension::<NonTransferable>(true).unwrap();
+
+        // 165 byte base, account type, TransferFeeAmount (4 + 8), then the
+        // empty NonTransferableAccount and ImmutableOwner entries.
+        assert_eq!(get_token_account_space(&mint_data, &[]).unwrap(), 186);
+        // ImmutableOwner is already required, MemoTransfer adds 4 + 1 bytes.
+        assert_eq!(
+            get_token_account_space(
+                &mint_data,
+                &[
+                    AccountExtensionConfig::ImmutableOwner,
+                    AccountExtensionConfig::MemoTransfer
+                ]
+            )
+            .unwrap(),
+            191
+        );
+
+        let mut legacy_mint_data = [0; Mint::LEN];
+        Mint {
+            decimals: 0,
+            is_initialized: true,
+            ..Default::default()
+        }
+        .pack_into_slice(&mut legacy_mint_data);
+        assert_eq!(
+            get_token_account_space(&legacy_mint_data, &[]).unwrap(),
+            Account::LEN
+        );
+    }
+}
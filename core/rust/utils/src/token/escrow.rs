@@ -0,0 +1,445 @@
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, program_error::ProgramError,
+};
+use spl_token_2022::{error::TokenError, state::Account};
+
+use super::{
+    assert_token_program_matches_package, create_token_account_with_extensions,
+    drain_and_close_token_account, get_mint_decimals, spl_token_transfer_checked_with_hook,
+    unpack_initialized, AccountExtensionConfig, CreateTokenAccountWithExtensionsParams,
+    DrainAndCloseTokenAccountParams, DrainMode, TokenTransferCheckedWithHookParams,
+};
+
+/// Creates a non-associated token account owned by `escrow_authority`, usually
+/// a PDA of the calling program. Token-2022 accounts also get ImmutableOwner so
+/// ownership can't be moved away from the escrow; legacy accounts keep the
+/// base 165 bytes.
+pub fn create_escrow_token_account(
+    params: CreateEscrowTokenAccountParams<'_, '_>,
+) -> ProgramResult {
+    let CreateEscrowTokenAccountParams {
+        escrow_token_account,
+        escrow_token_account_signer_seeds,
+        mint,
+        escrow_authority,
+        payer,
+        system_program,
+        token_program,
+    } = params;
+
+    assert_token_program_matches_package(&token_program, ProgramError::IncorrectProgramId)?;
+    if mint.owner != token_program.key {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let extensions: &[AccountExtensionConfig] = if *token_program.key == spl_token_2022::ID {
+        &[AccountExtensionConfig::ImmutableOwner]
+    } else {
+        &[]
+    };
+
+    create_token_account_with_extensions(CreateTokenAccountWithExtensionsParams {
+        account: escrow_token_account,
+        payer,
+        mint,
+        owner: escrow_authority,
+        extensions,
+        account_signer_seeds: escrow_token_account_signer_seeds,
+        owner_signer_seeds: None,
+        system_program,
+        token_program,
+    })
+}
+
+/// CreateEscrowTokenAccountParams
+pub struct CreateEscrowTokenAccountParams<'a: 'b, 'b> {
+    /// Uninitialized account, usually a PDA signing via `escrow_token_account_signer_seeds`.
+    pub escrow_token_account: AccountInfo<'a>,
+    /// escrow_token_account_signer_seeds
+    pub escrow_token_account_signer_seeds: Option<&'b [&'b [u8]]>,
+    /// mint
+    pub mint: AccountInfo<'a>,
+    /// Owner of the escrow token account.
+    pub escrow_authority: AccountInfo<'a>,
+    /// Funds the rent, refunded by `close_escrow_token_account`.
+    pub payer: AccountInfo<'a>,
+    /// system_program
+    pub system_program: AccountInfo<'a>,
+    /// token_program
+    pub token_program: AccountInfo<'a>,
+}
+
+/// Reads the escrow token account and checks it holds `mint` and is owned by
+/// `escrow_authority`. Returns its balance.
+fn get_escrow_balance(
+    escrow_token_account: &AccountInfo,
+    mint: &AccountInfo,
+    escrow_authority: &AccountInfo,
+) -> Result<u64, ProgramError> {
+    let data = escrow_token_account.try_borrow_data()?;
+    let account = unpack_initialized::<Account>(&data, ProgramError::UninitializedAccount)?;
+    if account.base.mint != *mint.key {
+        return Err(TokenError::MintMismatch.into());
+    }
+    if account.base.owner != *escrow_authority.key {
+        return Err(TokenError::OwnerMismatch.into());
+    }
+    Ok(account.base.amount)
+}
+
+/// Transfers `amount` from the depositor into the escrow. Returns the amount the
+/// escrow actually received, which is less than `amount` if the mint charges a
+/// transfer fee.
+pub fn escrow_deposit(params: EscrowDepositParams<'_, '_>) -> Result<u64, ProgramError> {
+    let EscrowDepositParams {
+        mint,
+        source,
+        escrow_token_account,
+        escrow_authority,
+        amount,
+        depositor,
+        depositor_signer_seeds,
+        token_program,
+        additional_accounts,
+    } = params;
+
+    let balance_before = get_escrow_balance(&escrow_token_account, &mint, &escrow_authority)?;
+
+    spl_token_transfer_checked_with_hook(TokenTransferCheckedWithHookParams {
+        mint: mint.clone(),
+        source,
+        destination: escrow_token_account.clone(),
+        amount,
+        authority: depositor,
+        authority_signer_seeds: depositor_signer_seeds,
+        token_program,
+        decimals: get_mint_decimals(&mint)?,
+        additional_accounts,
+    })?;
+
+    let balance_after = get_escrow_balance(&escrow_token_account, &mint, &escrow_authority)?;
+    balance_after
+        .checked_sub(balance_before)
+        .ok_or(ProgramError::ArithmeticOverflow)
+}
+
+/// EscrowDepositParams
+pub struct EscrowDepositParams<'a: 'b, 'b> {
+    /// mint
+    pub mint: AccountInfo<'a>,
+    /// Depositor's token account.
+    pub source: AccountInfo<'a>,
+    /// escrow_token_account
+    pub escrow_token_account: AccountInfo<'a>,
+    /// Expected owner of the escrow token account.
+    pub escrow_authority: AccountInfo<'a>,
+    /// amount
+    pub amount: u64,
+    /// Owner or delegate of `source`.
+    pub depositor: AccountInfo<'a>,
+    /// depositor_signer_seeds
+    pub depositor_signer_seeds: Option<&'b [&'b [u8]]>,
+    /// token_program
+    pub token_program: AccountInfo<'a>,
+    /// Transfer hook accounts, see `spl_token_transfer_checked_with_hook`.
+    pub additional_accounts: &'b [AccountInfo<'a>],
+}
+
+/// Transfers `amount` out of the escrow, signed by the escrow authority. Fails
+/// with `TokenError::InsufficientFunds` before the CPI if the escrow holds less.
+pub fn escrow_withdraw(params: EscrowWithdrawParams<'_, '_>) -> ProgramResult {
+    let EscrowWithdrawParams {
+        mint,
+        escrow_token_account,
+        destination,
+        amount,
+        escrow_authority,
+        escrow_authority_signer_seeds,
+        token_program,
+        additional_accounts,
+    } = params;
+
+    if get_escrow_balance(&escrow_token_account, &mint, &escrow_authority)? < amount {
+        return Err(TokenError::InsufficientFunds.into());
+    }
+
+    let decimals = get_mint_decimals(&mint)?;
+    spl_token_transfer_checked_with_hook(TokenTransferCheckedWithHookParams {
+        mint,
+        source: escrow_token_account,
+        destination,
+        amount,
+        authority: escrow_authority,
+        authority_signer_seeds: escrow_authority_signer_seeds,
+        token_program,
+        decimals,
+        additional_accounts,
+    })
+}
+
+/// EscrowWithdrawParams
+pub struct EscrowWithdrawParams<'a: 'b, 'b> {
+    /// mint
+    pub mint: AccountInfo<'a>,
+    /// escrow_token_account
+    pub escrow_token_account: AccountInfo<'a>,
+    /// destination
+    pub destination: AccountInfo<'a>,
+    /// amount
+    pub amount: u64,
+    /// Owner of the escrow token account.
+    pub escrow_authority: AccountInfo<'a>,
+    /// escrow_authority_signer_seeds
+    pub escrow_authority_signer_seeds: Option<&'b [&'b [u8]]>,
+    /// token_program
+    pub token_program: AccountInfo<'a>,
+    /// Transfer hook accounts, see `spl_token_transfer_checked_with_hook`.
+    pub additional_accounts: &'b [AccountInfo<'a>],
+}
+
+/// Moves any tokens left in the escrow to `token_destination`, then closes the
+/// account and refunds its rent to `rent_destination`, usually the initializer.
+/// Returns the amount of tokens moved.
+pub fn close_escrow_token_account(
+    params: CloseEscrowTokenAccountParams<'_, '_>,
+) -> Result<u64, ProgramError> {
+    let CloseEscrowTokenAccountParams {
+        escrow_token_account,
+        mint,
+        token_destination,
+        rent_destination,
+        escrow_authority,
+        escrow_authority_signer_seeds,
+        token_program,
+        additional_accounts,
+    } = params;
+
+    get_escrow_balance(&escrow_token_account, &mint, &escrow_authority)?;
+
+    drain_and_close_token_account(
+        DrainAndCloseTokenAccountParams {
+            account: escrow_token_account,
+            mint,
+            authority: escrow_authority,
+            destination: rent_destination,
+            authority_signer_seeds: escrow_authority_signer_seeds,
+            token_program,
+            additional_accounts,
+        },
+        DrainMode::TransferTo(token_destination),
+    )
+}
+
+/// CloseEscrowTokenAccountParams
+pub struct CloseEscrowTokenAccountParams<'a: 'b, 'b> {
+    /// escrow_token_account
+    pub escrow_token_account: AccountInfo<'a>,
+    /// mint
+    pub mint: AccountInfo<'a>,
+    /// Token account receiving any remaining balance.
+    pub token_destination: AccountInfo<'a>,
+    /// Receives the rent lamports.
+    pub rent_destination: AccountInfo<'a>,
+    /// Owner of the escrow token account.
+    pub escrow_authority: AccountInfo<'a>,
+    /// escrow_authority_signer_seeds
+    pub escrow_authority_signer_seeds: Option<&'b [&'b [u8]]>,
+    /// token_program
+    pub token_program: AccountInfo<'a>,
+    /// Transfer hook accounts, see `spl_token_transfer_checked_with_hook`.
+    pub additional_accounts: &'b [AccountInfo<'a>],
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_program::{program_pack::Pack, pubkey::Pubkey};
+    use spl_token_2022::state::AccountState;
+
+    use super::*;
+
+    #[test]
+    fn escrow_balance_checks_mint_and_owner() {
+        let [account_key, mint_key, authority_key, other_key] =
+            [(); 4].map(|_| Pubkey::new_unique());
+        let mut lamports = [0; 4];
+        let [account_lamports, mint_lamports, authority_lamports, other_lamports] = &mut lamports;
+        let mut account_data = vec![0; Account::LEN];
+        Account {
+            mint: mint_key,
+            owner: authority_key,
+            amount: 1_000,
+            state: AccountState::Initialized,
+            ..Default::default()
+        }
+        .pack_into_slice(&mut account_data);
+        let escrow_token_account = AccountInfo::new(
+            &account_key,
+            false,
+            true,
+            account_lamports,
+            &mut account_data,
+            &spl_token_2022::ID,
+            false,
+        );
+        let mint = AccountInfo::new(
+            &mint_key,
+            false,
+            false,
+            mint_lamports,
+            &mut [],
+            &spl_token_2022::ID,
+            false,
+        );
+        let escrow_authority = AccountInfo::new(
+            &authority_key,
+            false,
+            false,
+            authority_lamports,
+            &mut [],
+            &other_key,
+            false,
+        );
+        let other = AccountInfo::new(
+            &other_key,
+            false,
+            false,
+            other_lamports,
+            &mut [],
+            &other_key,
+            false,
+        );
+
+        assert_eq!(
+            get_escrow_balance(&escrow_token_account, &mint, &escrow_authority),
+            Ok(1_000)
+        );
+        assert_eq!(
+            get_escrow_balance(&escrow_token_account, &other, &escrow_authority),
+            Err(TokenError::MintMismatch.into())
+        );
+        assert_eq!(
+            get_escrow_balance(&escrow_token_account, &mint, &other),
+            Err(TokenError::OwnerMismatch.into())
+        );
+    }
+
+    #[test]
+    fn create_escrow_checks_token_program() {
+        let [account_key, mint_key, authority_key, payer_key, other_key] =
+            [(); 5].map(|_| Pubkey::new_unique());
+        let token_program_id = spl_token_2022::ID;
+        let legacy_token_program_id = crate::token::SPL_TOKEN_PROGRAM_IDS[0];
+        let system_program_id = Pubkey::default();
+        let mut lamports = [0; 5];
+        let [account_lamports, mint_lamports, legacy_mint_lamports, authority_lamports, payer_lamports] =
+            &mut lamports;
+        let mut program_lamports = [0; 4];
+        let [system_lamports, token_lamports, legacy_token_lamports, other_lamports] =
+            &mut program_lamports;
+        let escrow_token_account = AccountInfo::new(
+            &account_key,
+            false,
+            true,
+            account_lamports,
+            &mut [],
+            &system_program_id,
+            false,
+        );
+        let mint = AccountInfo::new(
+            &mint_key,
+            false,
+            false,
+            mint_lamports,
+            &mut [],
+            &token_program_id,
+            false,
+        );
+        let legacy_mint = AccountInfo::new(
+            &mint_key,
+            false,
+            false,
+            legacy_mint_lamports,
+            &mut [],
+            &legacy_token_program_id,
+            false,
+        );
+        let escrow_authority = AccountInfo::new(
+            &authority_key,
+            false,
+            false,
+            authority_lamports,
+            &mut [],
+            &system_program_id,
+            false,
+        );
+        let payer = AccountInfo::new(
+            &payer_key,
+            true,
+            true,
+            payer_lamports,
+            &mut [],
+            &system_program_id,
+            false,
+        );
+        let system_program = AccountInfo::new(
+            &system_program_id,
+            false,
+            false,
+            system_lamports,
+            &mut [],
+            &system_program_id,
+            true,
+        );
+        let token_program = AccountInfo::new(
+            &token_program_id,
+            false,
+            false,
+            token_lamports,
+            &mut [],
+            &system_program_id,
+            true,
+        );
+        let legacy_token_program = AccountInfo::new(
+            &legacy_token_program_id,
+            false,
+            false,
+            legacy_token_lamports,
+            &mut [],
+            &system_program_id,
+            true,
+        );
+        let other_program = AccountInfo::new(
+            &other_key,
+            false,
+            false,
+            other_lamports,
+            &mut [],
+            &system_program_id,
+            true,
+        );
+        let create = |mint, token_program| {
+            create_escrow_token_account(CreateEscrowTokenAccountParams {
+                escrow_token_account: escrow_token_account.clone(),
+                escrow_token_account_signer_seeds: None,
+                mint,
+                escrow_authority: escrow_authority.clone(),
+                payer: payer.clone(),
+                system_program: system_program.clone(),
+                token_program,
+            })
+        };
+
+        assert_eq!(
+            create(mint.clone(), other_program),
+            Err(ProgramError::IncorrectProgramId)
+        );
+        assert_eq!(
+            create(legacy_mint, token_program),
+            Err(ProgramError::IncorrectProgramId)
+        );
+        assert_eq!(
+            create(mint, legacy_token_program),
+            Err(ProgramError::IncorrectProgramId)
+        );
+    }
+}
@@ -0,0 +1,328 @@
+use solana_program::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    instruction::{AccountMeta, Instruction},
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    program_option::COption,
+};
+use spl_token_2022::{error::TokenError, state::Account};
+
+use super::{
+    assert_locked_by, get_mint_freeze_authority, spl_token_approve_checked,
+    spl_token_freeze_account, spl_token_revoke, spl_token_thaw_account, unpack_initialized,
+    TokenApproveParams, TokenFreezeAccountParams, TokenRevokeParams,
+};
+
+/// Token Metadata `FreezeDelegatedAccount` and `ThawDelegatedAccount` discriminators.
+const FREEZE_DELEGATED_ACCOUNT: u8 = 26;
+const THAW_DELEGATED_ACCOUNT: u8 = 27;
+
+/// Returns the master edition and Token Metadata program to freeze through, or
+/// `None` if `delegate` is the mint's freeze authority and can freeze directly.
+fn get_token_metadata_freeze_accounts<'a>(
+    mint: &AccountInfo<'a>,
+    delegate: &AccountInfo<'a>,
+    edition: Option<AccountInfo<'a>>,
+    token_metadata_program: Option<AccountInfo<'a>>,
+) -> Result<Option<(AccountInfo<'a>, AccountInfo<'a>)>, ProgramError> {
+    let freeze_authority = match get_mint_freeze_authority(mint)? {
+        COption::Some(freeze_authority) => freeze_authority,
+        COption::None => return Err(TokenError::MintCannotFreeze.into()),
+    };
+    if freeze_authority == *delegate.key {
+        return Ok(None);
+    }
+
+    match (edition, token_metadata_program) {
+        (Some(edition), Some(token_metadata_program)) if freeze_authority == *edition.key => {
+            if edition.owner != token_metadata_program.key {
+                return Err(ProgramError::IncorrectProgramId);
+            }
+            Ok(Some((edition, token_metadata_program)))
+        }
+        _ => {
+            msg!(
+                "Neither {} nor the given edition is the freeze authority of {}",
+                delegate.key,
+                mint.key
+            );
+            Err(TokenError::OwnerMismatch.into())
+        }
+    }
+}
+
+/// Invokes `FreezeDelegatedAccount` or `ThawDelegatedAccount` with the
+/// delegate, token account, edition, mint and token program, in that order.
+fn invoke_token_metadata_delegated_freeze<'a>(
+    discriminator: u8,
+    accounts: [&AccountInfo<'a>; 5],
+    token_metadata_program: &AccountInfo<'a>,
+    delegate_signer_seeds: Option<&[&[u8]]>,
+) -> ProgramResult {
+    let [delegate, token_account, edition, mint, token_program] = accounts;
+    let mut seeds: Vec<&[&[u8]]> = vec![];
+    if let Some(seed) = delegate_signer_seeds {
+        seeds.push(seed);
+    }
+
+    invoke_signed(
+        &Instruction {
+            program_id: *token_metadata_program.key,
+            accounts: vec![
+                AccountMeta::new_readonly(*delegate.key, true),
+                AccountMeta::new(*token_account.key, false),
+                AccountMeta::new_readonly(*edition.key, false),
+                AccountMeta::new_readonly(*mint.key, false),
+                AccountMeta::new_readonly(*token_program.key, false),
+            ],
+            data: vec![discriminator],
+        },
+        &[
+            delegate.clone(),
+            token_account.clone(),
+            edition.clone(),
+            mint.clone(),
+            token_program.clone(),
+            token_metadata_program.clone(),
+        ],
+        seeds.as_slice(),
+    )
+}
+
+/// Locks the tokens in place without taking custody: approves `delegate` for
+/// the whole balance, then freezes the account. If `delegate` is the mint's
+/// freeze authority it freezes directly; otherwise the freeze authority must be
+/// the Token Metadata master `edition`, as for Metaplex NFTs, and the account
+/// is frozen through Token Metadata's `FreezeDelegatedAccount`. Programmable
+/// NFTs must use Token Metadata's `Lock` instead.
+pub fn lock_token_account(params: LockTokenAccountParams<'_, '_>) -> ProgramResult {
+    let LockTokenAccountParams {
+        token_account,
+        mint,
+        owner,
+        owner_signer_seeds,
+        delegate,
+        delegate_signer_seeds,
+        token_program,
+        edition,
+        token_metadata_program,
+    } = params;
+
+    let token_metadata_freeze_accounts =
+        get_token_metadata_freeze_accounts(&mint, &delegate, edition, token_metadata_program)?;
+
+    let amount = {
+        let data = token_account.try_borrow_data()?;
+        let account = unpack_initialized::<Account>(&data, ProgramError::UninitializedAccount)?;
+        if account.base.mint != *mint.key {
+            return Err(TokenError::MintMismatch.into());
+        }
+        account.base.amount
+    };
+
+    spl_token_approve_checked(TokenApproveParams {
+        mint: mint.clone(),
+        source: token_account.clone(),
+        delegate: delegate.clone(),
+        amount,
+        owner,
+        owner_signer_seeds,
+        token_program: token_program.clone(),
+    })?;
+
+    match token_metadata_freeze_accounts {
+        Some((edition, token_metadata_program)) => invoke_token_metadata_delegated_freeze(
+            FREEZE_DELEGATED_ACCOUNT,
+            [&delegate, &token_account, &edition, &mint, &token_program],
+            &token_metadata_program,
+            delegate_signer_seeds,
+        ),
+        None => spl_token_freeze_account(TokenFreezeAccountParams {
+            account: token_account,
+            mint,
+            freeze_authority: delegate,
+            freeze_authority_signer_seeds: delegate_signer_seeds,
+            token_program,
+        }),
+    }
+}
+
+/// LockTokenAccountParams
+pub struct LockTokenAccountParams<'a: 'b, 'b> {
+    /// Token account to lock.
+    pub token_account: AccountInfo<'a>,
+    /// mint
+    pub mint: AccountInfo<'a>,
+    /// Owner of the token account, signing the approval.
+    pub owner: AccountInfo<'a>,
+    /// owner_signer_seeds
+    pub owner_signer_seeds: Option<&'b [&'b [u8]]>,
+    /// Usually a PDA of the calling program. Either the mint's freeze
+    /// authority, or frozen through `edition`.
+    pub delegate: AccountInfo<'a>,
+    /// delegate_signer_seeds
+    pub delegate_signer_seeds: Option<&'b [&'b [u8]]>,
+    /// token_program
+    pub token_program: AccountInfo<'a>,
+    /// Master edition of the mint, needed when it is the mint's freeze authority.
+    pub edition: Option<AccountInfo<'a>>,
+    /// Token Metadata program, needed with `edition`.
+    pub token_metadata_program: Option<AccountInfo<'a>>,
+}
+
+/// Reverts `lock_token_account`: thaws the account with `delegate` signing,
+/// directly or through Token Metadata's `ThawDelegatedAccount`, then revokes the
+/// delegation, which only the owner can sign for. Fails with
+/// `ProgramError::InvalidAccountData` if the account is not locked by `delegate`.
+pub fn unlock_token_account(params: LockTokenAccountParams<'_, '_>) -> ProgramResult {
+    let LockTokenAccountParams {
+        token_account,
+        mint,
+        owner,
+        owner_signer_seeds,
+        delegate,
+        delegate_signer_seeds,
+        token_program,
+        edition,
+        token_metadata_program,
+    } = params;
+
+    assert_locked_by(
+        &token_account,
+        delegate.key,
+        ProgramError::InvalidAccountData,
+    )?;
+
+    match get_token_metadata_freeze_accounts(&mint, &delegate, edition, token_metadata_program)? {
+        Some((edition, token_metadata_program)) => invoke_token_metadata_delegated_freeze(
+            THAW_DELEGATED_ACCOUNT,
+            [&delegate, &token_account, &edition, &mint, &token_program],
+            &token_metadata_program,
+            delegate_signer_seeds,
+        )?,
+        None => spl_token_thaw_account(TokenFreezeAccountParams {
+            account: token_account.clone(),
+            mint,
+            freeze_authority: delegate,
+            freeze_authority_signer_seeds: delegate_signer_seeds,
+            token_program: token_program.clone(),
+        })?,
+    }
+
+    spl_token_revoke(TokenRevokeParams {
+        source: token_account,
+        owner,
+        owner_signer_seeds,
+        token_program,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_program::{program_pack::Pack, pubkey::Pubkey};
+    use spl_token_2022::state::Mint;
+
+    use super::*;
+
+    #[test]
+    fn token_metadata_freeze_accounts() {
+        let [mint_key, delegate_key, edition_key, other_key] =
+            [(); 4].map(|_| Pubkey::new_unique());
+        let token_metadata_program_id = Pubkey::new_unique();
+        let mut lamports = [0; 5];
+        let [mint_lamports, delegate_lamports, edition_lamports, program_lamports, other_lamports] =
+            &mut lamports;
+        let mut mint_data = [0; Mint::LEN];
+        let mint = AccountInfo::new(
+            &mint_key,
+            false,
+            false,
+            mint_lamports,
+            &mut mint_data,
+            &spl_token_2022::ID,
+            false,
+        );
+        let delegate = AccountInfo::new(
+            &delegate_key,
+            true,
+            false,
+            delegate_lamports,
+            &mut [],
+            &other_key,
+            false,
+        );
+        let edition = AccountInfo::new(
+            &edition_key,
+            false,
+            false,
+            edition_lamports,
+            &mut [],
+            &token_metadata_program_id,
+            false,
+        );
+        let token_metadata_program = AccountInfo::new(
+            &token_metadata_program_id,
+            false,
+            false,
+            program_lamports,
+            &mut [],
+            &other_key,
+            true,
+        );
+        let other_program = AccountInfo::new(
+            &other_key,
+            false,
+            false,
+            other_lamports,
+            &mut [],
+            &other_key,
+            true,
+        );
+        let set_freeze_authority = |freeze_authority: COption<Pubkey>| {
+            Mint {
+                freeze_authority,
+                is_initialized: true,
+                ..Default::default()
+            }
+            .pack_into_slice(&mut mint.try_borrow_mut_data().unwrap())
+        };
+        let freeze_accounts = |edition, token_metadata_program| {
+            get_token_metadata_freeze_accounts(&mint, &delegate, edition, token_metadata_program)
+                .map(|accounts| accounts.map(|(edition, program)| (*edition.key, *program.key)))
+        };
+
+        set_freeze_authority(COption::Some(delegate_key));
+        assert_eq!(
+            freeze_accounts(Some(edition.clone()), Some(token_metadata_program.clone())),
+            Ok(None)
+        );
+
+        set_freeze_authority(COption::Some(edition_key));
+        assert_eq!(
+            freeze_accounts(Some(edition.clone()), Some(token_metadata_program.clone())),
+            Ok(Some((edition_key, token_metadata_program_id)))
+        );
+        assert_eq!(
+            freeze_accounts(Some(edition.clone()), Some(other_program.clone())),
+            Err(ProgramError::IncorrectProgramId)
+        );
+        assert_eq!(
+            freeze_accounts(None, None),
+            Err(TokenError::OwnerMismatch.into())
+        );
+
+        set_freeze_authority(COption::Some(other_key));
+        assert_eq!(
+            freeze_accounts(Some(edition.clone()), Some(token_metadata_program.clone())),
+            Err(TokenError::OwnerMismatch.into())
+        );
+
+        set_freeze_authority(COption::None);
+        assert_eq!(
+            freeze_accounts(Some(edition.clone()), Some(token_metadata_program.clone())),
+            Err(TokenError::MintCannotFreeze.into())
+        );
+    }
+}
@@ -1,7 +1,23 @@
+pub use amount::*;
 pub use assertions::*;
+pub use close::*;
 pub use cpi::*;
+pub use create::*;
+pub use escrow::*;
+pub use lock::*;
+pub use native::*;
+pub use payout::*;
+pub use token_metadata::*;
 pub use utils::*;
 
+mod amount;
 mod assertions;
+mod close;
 mod cpi;
+mod create;
+mod escrow;
+mod lock;
+mod native;
+mod payout;
+mod token_metadata;
 mod utils;
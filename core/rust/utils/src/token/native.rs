@@ -0,0 +1,284 @@
+use solana_program::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey,
+    pubkey::Pubkey,
+    rent::Rent,
+    sysvar::Sysvar,
+};
+use solana_system_interface::instruction as system_instruction;
+use spl_token_2022::{error::TokenError, state::Account};
+
+use super::{
+    create_token_account_with_extensions, spl_token_close, unpack_initialized,
+    CreateTokenAccountWithExtensionsParams, TokenCloseParams, SPL_TOKEN_PROGRAM_IDS,
+};
+
+/// Native mints of the token programs, in the same order as `SPL_TOKEN_PROGRAM_IDS`.
+pub static SPL_TOKEN_NATIVE_MINT_IDS: [Pubkey; 2] = [
+    pubkey!("So11111111111111111111111111111111111111112"),
+    pubkey!("9pan9bMn5HatX4EJdBwg9VgCa7Uz5HL8N1m5D3NdXejP"),
+];
+
+/// Returns the native mint of the given token program.
+pub fn get_native_mint(token_program_id: &Pubkey) -> Result<Pubkey, ProgramError> {
+    SPL_TOKEN_PROGRAM_IDS
+        .iter()
+        .position(|program_id| program_id == token_program_id)
+        .map(|index| SPL_TOKEN_NATIVE_MINT_IDS[index])
+        .ok_or(ProgramError::IncorrectProgramId)
+}
+
+/// Wraps `amount` lamports into the native token account, creating and
+/// initializing it first if it does not exist yet. The rent-exempt reserve of a
+/// new account is paid on top of `amount`, so the account ends up holding
+/// exactly `amount` more wrapped SOL. An existing account must hold `mint` and
+/// be owned by `owner`.
+pub fn wrap_sol(params: WrapSolParams<'_, '_>) -> ProgramResult {
+    let WrapSolParams {
+        token_account,
+        mint,
+        owner,
+        payer,
+        amount,
+        token_account_signer_seeds,
+        payer_signer_seeds,
+        system_program,
+        token_program,
+    } = params;
+
+    if *mint.key != get_native_mint(token_program.key)? {
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    let mut seeds: Vec<&[&[u8]]> = vec![];
+    if let Some(seed) = payer_signer_seeds {
+        seeds.push(seed);
+    }
+
+    let create = token_account.data_is_empty();
+    // The rent of a new account is sent together with `amount` so a PDA payer
+    // can sign for it; account creation then finds it already funded.
+    let lamports = if create {
+        let rent_exempt_reserve = Rent::get()?
+            .minimum_balance(Account::LEN)
+            .saturating_sub(token_account.lamports());
+        amount
+            .checked_add(rent_exempt_reserve)
+            .ok_or(ProgramError::ArithmeticOverflow)?
+    } else {
+        let data = token_account.try_borrow_data()?;
+        let account = unpack_initialized::<Account>(&data, ProgramError::UninitializedAccount)?;
+        if account.base.mint != *mint.key {
+            return Err(TokenError::MintMismatch.into());
+        }
+        if account.base.owner != *owner.key {
+            return Err(TokenError::OwnerMismatch.into());
+        }
+        amount
+    };
+
+    if lamports > 0 {
+        invoke_signed(
+            &system_instruction::transfer(payer.key, token_account.key, lamports),
+            &[payer.clone(), token_account.clone(), system_program.clone()],
+            seeds.as_slice(),
+        )?;
+    }
+
+    if create {
+        create_token_account_with_extensions(CreateTokenAccountWithExtensionsParams {
+            account: token_account.clone(),
+            payer: payer.clone(),
+            mint,
+            owner,
+            extensions: &[],
+            account_signer_seeds: token_account_signer_seeds,
+            owner_signer_seeds: None,
+            system_program: system_program.clone(),
+            token_program: token_program.clone(),
+        })?;
+    }
+
+    invoke(
+        &spl_token_2022::instruction::sync_native(token_program.key, token_account.key)?,
+        &[token_account, token_program],
+    )
+}
+
+/// WrapSolParams
+pub struct WrapSolParams<'a: 'b, 'b> {
+    /// Native token account, created if it holds no data.
+    pub token_account: AccountInfo<'a>,
+    /// Native mint of `token_program`.
+    pub mint: AccountInfo<'a>,
+    /// owner
+    pub owner: AccountInfo<'a>,
+    /// System account funding the rent and the wrapped lamports.
+    pub payer: AccountInfo<'a>,
+    /// Lamports to wrap, excluding rent.
+    pub amount: u64,
+    /// Only needed when the token account is created and is a PDA.
+    pub token_account_signer_seeds: Option<&'b [&'b [u8]]>,
+    /// Signs for both the rent and the wrapped lamports when `payer` is a PDA.
+    pub payer_signer_seeds: Option<&'b [&'b [u8]]>,
+    /// system_program
+    pub system_program: AccountInfo<'a>,
+    /// token_program
+    pub token_program: AccountInfo<'a>,
+}
+
+/// Closes the native token account, sending all its lamports to `destination`.
+/// Returns the wrapped amount, i.e. the lamports released minus the rent-exempt reserve.
+pub fn unwrap_sol(params: UnwrapSolParams<'_, '_>) -> Result<u64, ProgramError> {
+    let UnwrapSolParams {
+        token_account,
+        destination,
+        owner,
+        owner_signer_seeds,
+        token_program,
+    } = params;
+
+    let amount = {
+        let data = token_account.try_borrow_data()?;
+        let account = unpack_initialized::<Account>(&data, ProgramError::UninitializedAccount)?;
+        // Lamports sent without a sync_native are unwrapped too, so count from the
+        // balance rather than the token amount.
+        let rent_exempt_reserve = account
+            .base
+            .is_native
+            .ok_or(ProgramError::InvalidAccountData)?;
+        token_account.lamports().saturating_sub(rent_exempt_reserve)
+    };
+
+    spl_token_close(TokenCloseParams {
+        account: token_account,
+        destination,
+        owner,
+        authority_signer_seeds: owner_signer_seeds,
+        token_program,
+    })?;
+
+    Ok(amount)
+}
+
+/// UnwrapSolParams
+pub struct UnwrapSolParams<'a: 'b, 'b> {
+    /// Native token account to close.
+    pub token_account: AccountInfo<'a>,
+    /// Receives the wrapped amount and the rent-exempt reserve.
+    pub destination: AccountInfo<'a>,
+    /// owner
+    pub owner: AccountInfo<'a>,
+    /// owner_signer_seeds
+    pub owner_signer_seeds: Option<&'b [&'b [u8]]>,
+    /// token_program
+    pub token_program: AccountInfo<'a>,
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_program::program_option::COption;
+    use spl_token_2022::state::AccountState;
+
+    use super::*;
+
+    #[test]
+    fn native_mint_of_each_token_program() {
+        assert_eq!(
+            get_native_mint(&SPL_TOKEN_PROGRAM_IDS[0]),
+            Ok(pubkey!("So11111111111111111111111111111111111111112"))
+        );
+        assert_eq!(
+            get_native_mint(&spl_token_2022::ID),
+            Ok(spl_token_2022::native_mint::ID)
+        );
+        assert_eq!(
+            get_native_mint(&Pubkey::new_unique()),
+            Err(ProgramError::IncorrectProgramId)
+        );
+    }
+
+    #[test]
+    fn unwrap_sol_counts_lamports_above_reserve() {
+        let rent_exempt_reserve = 2_039_280;
+        let owner_key = Pubkey::new_unique();
+        let account_key = Pubkey::new_unique();
+        let destination_key = Pubkey::new_unique();
+        let token_program_id = spl_token_2022::ID;
+        let system_program_id = Pubkey::default();
+        // 500 lamports were sent without a sync_native, so the token amount lags behind.
+        let mut lamports = [rent_exempt_reserve + 1_500, 0, 0, 0];
+        let [account_lamports, owner_lamports, destination_lamports, program_lamports] =
+            &mut lamports;
+        let mut account_data = vec![0; Account::LEN];
+        let pack_account = |is_native, account_data: &mut [u8]| {
+            Account {
+                mint: spl_token_2022::native_mint::ID,
+                owner: owner_key,
+                amount: 1_000,
+                state: AccountState::Initialized,
+                is_native,
+                ..Default::default()
+            }
+            .pack_into_slice(account_data)
+        };
+        pack_account(COption::Some(rent_exempt_reserve), &mut account_data);
+        let token_account = AccountInfo::new(
+            &account_key,
+            false,
+            true,
+            account_lamports,
+            &mut account_data,
+            &token_program_id,
+            false,
+        );
+        let owner = AccountInfo::new(
+            &owner_key,
+            true,
+            false,
+            owner_lamports,
+            &mut [],
+            &system_program_id,
+            false,
+        );
+        let destination = AccountInfo::new(
+            &destination_key,
+            false,
+            true,
+            destination_lamports,
+            &mut [],
+            &system_program_id,
+            false,
+        );
+        let token_program = AccountInfo::new(
+            &token_program_id,
+            false,
+            false,
+            program_lamports,
+            &mut [],
+            &system_program_id,
+            true,
+        );
+        let unwrap = || {
+            unwrap_sol(UnwrapSolParams {
+                token_account: token_account.clone(),
+                destination: destination.clone(),
+                owner: owner.clone(),
+                owner_signer_seeds: None,
+                token_program: token_program.clone(),
+            })
+        };
+
+        assert_eq!(unwrap(), Ok(1_500));
+
+        pack_account(
+            COption::None,
+            &mut token_account.try_borrow_mut_data().unwrap(),
+        );
+        assert_eq!(unwrap(), Err(ProgramError::InvalidAccountData));
+    }
+}
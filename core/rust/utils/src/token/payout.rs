@@ -0,0 +1,268 @@
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, program::invoke_signed,
+    program_error::ProgramError,
+};
+use spl_associated_token_account_interface::{
+    address::get_associated_token_address_with_program_id,
+    instruction::create_associated_token_account_idempotent,
+};
+
+use super::{spl_token_transfer_checked_from_mint, TokenTransferParams};
+
+/// Transfers `amount` to the wallet's associated token account for the mint's
+/// token program, creating the ATA first if it doesn't exist.
+pub fn transfer_to_wallet(params: TransferToWalletParams<'_, '_>) -> ProgramResult {
+    let TransferToWalletParams {
+        mint,
+        source,
+        wallet,
+        wallet_token_account,
+        amount,
+        authority,
+        authority_signer_seeds,
+        payer,
+        payer_signer_seeds,
+        system_program,
+        token_program,
+        associated_token_program,
+    } = params;
+
+    if mint.owner != token_program.key {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    let associated_token_address =
+        get_associated_token_address_with_program_id(wallet.key, mint.key, token_program.key);
+    if associated_token_address != *wallet_token_account.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    if wallet_token_account.data_is_empty() {
+        let mut seeds: Vec<&[&[u8]]> = vec![];
+        if let Some(seed) = payer_signer_seeds {
+            seeds.push(seed);
+        }
+        invoke_signed(
+            &create_associated_token_account_idempotent(
+                payer.key,
+                wallet.key,
+                mint.key,
+                token_program.key,
+            ),
+            &[
+                payer,
+                wallet_token_account.clone(),
+                wallet,
+                mint.clone(),
+                system_program,
+                token_program.clone(),
+                associated_token_program,
+            ],
+            seeds.as_slice(),
+        )?;
+    }
+
+    spl_token_transfer_checked_from_mint(TokenTransferParams {
+        mint,
+        source,
+        destination: wallet_token_account,
+        amount,
+        authority,
+        authority_signer_seeds,
+        token_program,
+    })
+}
+
+/// TransferToWalletParams
+pub struct TransferToWalletParams<'a: 'b, 'b> {
+    /// mint
+    pub mint: AccountInfo<'a>,
+    /// source
+    pub source: AccountInfo<'a>,
+    /// Wallet receiving the tokens.
+    pub wallet: AccountInfo<'a>,
+    /// Associated token account of `wallet`, may not exist yet.
+    pub wallet_token_account: AccountInfo<'a>,
+    /// amount
+    pub amount: u64,
+    /// authority
+    pub authority: AccountInfo<'a>,
+    /// authority_signer_seeds
+    pub authority_signer_seeds: Option<&'b [&'b [u8]]>,
+    /// Pays for the associated token account if it has to be created.
+    pub payer: AccountInfo<'a>,
+    /// payer_signer_seeds
+    pub payer_signer_seeds: Option<&'b [&'b [u8]]>,
+    /// system_program
+    pub system_program: AccountInfo<'a>,
+    /// token_program
+    pub token_program: AccountInfo<'a>,
+    /// associated_token_program
+    pub associated_token_program: AccountInfo<'a>,
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_program::{program_pack::Pack, pubkey::Pubkey};
+    use spl_token_2022::state::{Account, AccountState, Mint};
+
+    use super::*;
+
+    #[test]
+    fn transfer_to_wallet_requires_wallet_ata() {
+        let [mint_key, source_key, wallet_key, authority_key, payer_key, other_key] =
+            [(); 6].map(|_| Pubkey::new_unique());
+        let token_program_id = spl_token_2022::ID;
+        let legacy_token_program_id = crate::token::SPL_TOKEN_PROGRAM_IDS[0];
+        let associated_token_program_id = spl_associated_token_account_interface::program::ID;
+        let system_program_id = Pubkey::default();
+        let ata_key =
+            get_associated_token_address_with_program_id(&wallet_key, &mint_key, &token_program_id);
+        // The wallet's ATA for the other token program must not be accepted either.
+        let legacy_ata_key = get_associated_token_address_with_program_id(
+            &wallet_key,
+            &mint_key,
+            &legacy_token_program_id,
+        );
+        let mut lamports = [0; 8];
+        let [mint_lamports, source_lamports, wallet_lamports, authority_lamports, payer_lamports, ata_lamports, legacy_ata_lamports, other_lamports] =
+            &mut lamports;
+        let mut program_lamports = [0; 3];
+        let [system_lamports, token_lamports, associated_token_lamports] = &mut program_lamports;
+        let mut mint_data = vec![0; Mint::LEN];
+        Mint {
+            decimals: 6,
+            is_initialized: true,
+            ..Default::default()
+        }
+        .pack_into_slice(&mut mint_data);
+        let mut account_data = vec![0; Account::LEN];
+        Account {
+            mint: mint_key,
+            owner: authority_key,
+            amount: 1_000,
+            state: AccountState::Initialized,
+            ..Default::default()
+        }
+        .pack_into_slice(&mut account_data);
+        let mut ata_data = account_data.clone();
+        let mint = AccountInfo::new(
+            &mint_key,
+            false,
+            false,
+            mint_lamports,
+            &mut mint_data,
+            &token_program_id,
+            false,
+        );
+        let source = AccountInfo::new(
+            &source_key,
+            false,
+            true,
+            source_lamports,
+            &mut account_data,
+            &token_program_id,
+            false,
+        );
+        let wallet = AccountInfo::new(
+            &wallet_key,
+            false,
+            false,
+            wallet_lamports,
+            &mut [],
+            &system_program_id,
+            false,
+        );
+        let authority = AccountInfo::new(
+            &authority_key,
+            true,
+            false,
+            authority_lamports,
+            &mut [],
+            &system_program_id,
+            false,
+        );
+        let payer = AccountInfo::new(
+            &payer_key,
+            true,
+            true,
+            payer_lamports,
+            &mut [],
+            &system_program_id,
+            false,
+        );
+        let ata = AccountInfo::new(
+            &ata_key,
+            false,
+            true,
+            ata_lamports,
+            &mut ata_data,
+            &token_program_id,
+            false,
+        );
+        let legacy_ata = AccountInfo::new(
+            &legacy_ata_key,
+            false,
+            true,
+            legacy_ata_lamports,
+            &mut [],
+            &system_program_id,
+            false,
+        );
+        let other = AccountInfo::new(
+            &other_key,
+            false,
+            true,
+            other_lamports,
+            &mut [],
+            &system_program_id,
+            false,
+        );
+        let system_program = AccountInfo::new(
+            &system_program_id,
+            false,
+            false,
+            system_lamports,
+            &mut [],
+            &system_program_id,
+            true,
+        );
+        let token_program = AccountInfo::new(
+            &token_program_id,
+            false,
+            false,
+            token_lamports,
+            &mut [],
+            &system_program_id,
+            true,
+        );
+        let associated_token_program = AccountInfo::new(
+            &associated_token_program_id,
+            false,
+            false,
+            associated_token_lamports,
+            &mut [],
+            &system_program_id,
+            true,
+        );
+        let transfer = |wallet_token_account| {
+            transfer_to_wallet(TransferToWalletParams {
+                mint: mint.clone(),
+                source: source.clone(),
+                wallet: wallet.clone(),
+                wallet_token_account,
+                amount: 1_000,
+                authority: authority.clone(),
+                authority_signer_seeds: None,
+                payer: payer.clone(),
+                payer_signer_seeds: None,
+                system_program: system_program.clone(),
+                token_program: token_program.clone(),
+                associated_token_program: associated_token_program.clone(),
+            })
+        };
+
+        assert_eq!(transfer(ata), Ok(()));
+        assert_eq!(transfer(legacy_ata), Err(ProgramError::InvalidSeeds));
+        assert_eq!(transfer(other), Err(ProgramError::InvalidSeeds));
+    }
+}
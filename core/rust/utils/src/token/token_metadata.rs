@@ -0,0 +1,273 @@
+use solana_program::{account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey};
+use spl_discriminator::SplDiscriminate;
+use spl_token_2022::{
+    extension::{metadata_pointer::MetadataPointer, BaseStateWithExtensions},
+    state::Mint,
+};
+use spl_token_metadata_interface::state::TokenMetadata;
+
+use super::unpack;
+use crate::borsh_reader::BorshReader;
+
+/// Returns the address the mint's MetadataPointer extension points at, if any.
+pub fn get_metadata_pointer_address(
+    mint_info: &AccountInfo,
+) -> Result<Option<Pubkey>, ProgramError> {
+    let data = mint_info.try_borrow_data()?;
+    let mint = unpack::<Mint>(&data)?;
+
+    Ok(mint
+        .get_extension::<MetadataPointer>()
+        .ok()
+        .and_then(|pointer| pointer.metadata_address.into()))
+}
+
+/// Zero-copy view over a borsh-encoded `TokenMetadata`. Strings borrow from the
+/// account data and additional metadata is only walked when asked for.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TokenMetadataRef<'data> {
+    /// update_authority
+    pub update_authority: Option<Pubkey>,
+    /// mint
+    pub mint: Pubkey,
+    /// name
+    pub name: &'data str,
+    /// symbol
+    pub symbol: &'data str,
+    /// uri
+    pub uri: &'data str,
+    additional_metadata: &'data [u8],
+}
+
+impl<'data> TokenMetadataRef<'data> {
+    /// Reads the TokenMetadata extension stored on a Token-2022 mint.
+    pub fn from_mint_data(data: &'data [u8]) -> Result<Self, ProgramError> {
+        let mint = unpack::<Mint>(data)?;
+        let value = mint.get_extension_bytes::<TokenMetadata>()?;
+        // The extension bytes borrow from `mint`, re-slice them from `data` so the
+        // view can outlive it.
+        let start = value.as_ptr() as usize - data.as_ptr() as usize;
+
+        Self::unpack(&data[start..start + value.len()])
+    }
+
+    /// Reads the first TokenMetadata entry of an account laid out with
+    /// spl-type-length-value, e.g. the target of a MetadataPointer owned by a
+    /// metadata program other than Token-2022.
+    pub fn from_tlv_data(data: &'data [u8]) -> Result<Self, ProgramError> {
+        let mut offset = 0;
+        while let Some(header) = data.get(offset..offset + 12) {
+            let length = u32::from_le_bytes([header[8], header[9], header[10], header[11]]);
+            let length = usize::try_from(length).map_err(|_| ProgramError::InvalidAccountData)?;
+            let value = data
+                .get(offset + 12..offset + 12 + length)
+                .ok_or(ProgramError::InvalidAccountData)?;
+            if header[..8] == *TokenMetadata::SPL_DISCRIMINATOR_SLICE {
+                return Self::unpack(value);
+            }
+            if header[..8] == [0; 8] {
+                break;
+            }
+            offset += 12 + length;
+        }
+
+        Err(ProgramError::InvalidAccountData)
+    }
+
+    /// Reads the TokenMetadata pointed at by a mint, either from the mint itself
+    /// or from an external account, and checks the metadata belongs to the mint.
+    pub fn from_account_data(
+        mint: &Pubkey,
+        metadata_address: &Pubkey,
+        data: &'data [u8],
+    ) -> Result<Self, ProgramError> {
+        let metadata = if metadata_address == mint {
+            Self::from_mint_data(data)?
+        } else {
+            Self::from_tlv_data(data)?
+        };
+
+        if metadata.mint != *mint {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        Ok(metadata)
+    }
+
+    /// Parses the value of a TokenMetadata TLV entry.
+    pub fn unpack(value: &'data [u8]) -> Result<Self, ProgramError> {
+        let mut reader = BorshReader { data: value };
+        let update_authority = reader.read_pubkey()?;
+        let mint = reader.read_pubkey()?;
+        let name = reader.read_str()?;
+        let symbol = reader.read_str()?;
+        let uri = reader.read_str()?;
+
+        Ok(Self {
+            update_authority: (update_authority != Pubkey::default()).then_some(update_authority),
+            mint,
+            name,
+            symbol,
+            uri,
+            additional_metadata: reader.data,
+        })
+    }
+
+    /// Iterates the additional metadata key/value pairs in order. A missing or
+    /// truncated length prefix is returned as the first item's error.
+    pub fn additional_metadata(&self) -> AdditionalMetadataIter<'data> {
+        let mut reader = BorshReader {
+            data: self.additional_metadata,
+        };
+        let remaining = reader.read_u32();
+
+        AdditionalMetadataIter { reader, remaining }
+    }
+
+    /// Looks up a single additional metadata value without decoding the other entries.
+    pub fn get_additional_metadata(&self, key: &str) -> Result<Option<&'data str>, ProgramError> {
+        for entry in self.additional_metadata() {
+            let (entry_key, value) = entry?;
+            if entry_key == key {
+                return Ok(Some(value));
+            }
+        }
+
+        Ok(None)
+    }
+}
+
+/// Iterator returned by `TokenMetadataRef::additional_metadata`.
+pub struct AdditionalMetadataIter<'data> {
+    reader: BorshReader<'data>,
+    remaining: Result<u32, ProgramError>,
+}
+
+impl<'data> Iterator for AdditionalMetadataIter<'data> {
+    type Item = Result<(&'data str, &'data str), ProgramError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let remaining = match std::mem::replace(&mut self.remaining, Ok(0)) {
+            Ok(0) => return None,
+            Ok(remaining) => remaining,
+            Err(err) => return Some(Err(err)),
+        };
+
+        let entry = self
+            .reader
+            .read_str()
+            .and_then(|key| Ok((key, self.reader.read_str()?)));
+        if entry.is_ok() {
+            self.remaining = Ok(remaining - 1);
+        }
+
+        Some(entry)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_program::program_pack::Pack;
+    use spl_token_2022::extension::{
+        BaseStateWithExtensionsMut, ExtensionType, StateWithExtensionsMut,
+    };
+    use spl_token_metadata_interface::borsh;
+
+    use super::*;
+
+    fn token_metadata() -> TokenMetadata {
+        TokenMetadata {
+            update_authority: Some(Pubkey::new_unique()).try_into().unwrap(),
+            mint: Pubkey::new_unique(),
+            name: "Name".to_string(),
+            symbol: "SYM".to_string(),
+            uri: "https://example.com".to_string(),
+            additional_metadata: vec![
+                ("first".to_string(), "1".to_string()),
+                ("second".to_string(), "2".to_string()),
+            ],
+        }
+    }
+
+    #[test]
+    fn unpack_reads_fields() {
+        let metadata = token_metadata();
+        let value = borsh::to_vec(&metadata).unwrap();
+        let view = TokenMetadataRef::unpack(&value).unwrap();
+
+        assert_eq!(view.update_authority, metadata.update_authority.into());
+        assert_eq!(view.mint, metadata.mint);
+        assert_eq!(view.name, "Name");
+        assert_eq!(view.symbol, "SYM");
+        assert_eq!(view.uri, "https://example.com");
+        assert_eq!(view.get_additional_metadata("second").unwrap(), Some("2"));
+        assert_eq!(view.get_additional_metadata("third").unwrap(), None);
+        assert_eq!(view.additional_metadata().count(), 2);
+    }
+
+    #[test]
+    fn unpack_from_tlv_data() {
+        let metadata = token_metadata();
+        let value = borsh::to_vec(&metadata).unwrap();
+        let mut data = TokenMetadata::SPL_DISCRIMINATOR_SLICE.to_vec();
+        data.extend_from_slice(&(value.len() as u32).to_le_bytes());
+        data.extend_from_slice(&value);
+
+        let view = TokenMetadataRef::from_tlv_data(&data).unwrap();
+        assert_eq!(view.name, "Name");
+        assert!(
+            TokenMetadataRef::from_account_data(&metadata.mint, &Pubkey::new_unique(), &data)
+                .is_ok()
+        );
+        assert!(TokenMetadataRef::from_account_data(
+            &Pubkey::new_unique(),
+            &Pubkey::new_unique(),
+            &data
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn unpack_from_mint_data() {
+        let metadata = token_metadata();
+        let space =
+            ExtensionType::try_calculate_account_len::<Mint>(&[ExtensionType::MetadataPointer])
+                .unwrap()
+                + metadata.tlv_size_of().unwrap();
+        let mut data = vec![0; space];
+        let mut mint = StateWithExtensionsMut::<Mint>::unpack_uninitialized(&mut data).unwrap();
+        mint.base = Mint {
+            decimals: 0,
+            is_initialized: true,
+            ..Default::default()
+        };
+        mint.pack_base();
+        mint.init_account_type().unwrap();
+        mint.init_extension::<MetadataPointer>(true).unwrap();
+        mint.init_variable_len_extension(&metadata, false).unwrap();
+
+        let view = TokenMetadataRef::from_mint_data(&data).unwrap();
+        assert_eq!(view.mint, metadata.mint);
+        assert_eq!(view.uri, "https://example.com");
+        assert_eq!(view.get_additional_metadata("first").unwrap(), Some("1"));
+        assert!(TokenMetadataRef::from_account_data(&metadata.mint, &metadata.mint, &data).is_ok());
+        assert!(TokenMetadataRef::from_mint_data(&data[..Mint::LEN]).is_err());
+    }
+
+    #[test]
+    fn unpack_rejects_truncated_data() {
+        let value = borsh::to_vec(&token_metadata()).unwrap();
+
+        assert!(TokenMetadataRef::unpack(&value[..70]).is_err());
+        let view = TokenMetadataRef::unpack(&value[..value.len() - 1]).unwrap();
+        assert!(view.get_additional_metadata("second").is_err());
+
+        // Cut inside, then right before, the additional metadata length prefix.
+        let prefix_start = value.len() - 4 - (4 + 5 + 4 + 1) - (4 + 6 + 4 + 1);
+        for end in [prefix_start + 2, prefix_start] {
+            let view = TokenMetadataRef::unpack(&value[..end]).unwrap();
+            assert!(view.get_additional_metadata("first").is_err());
+            assert_eq!(view.additional_metadata().count(), 1);
+        }
+    }
+}
@@ -1,9 +1,18 @@
 use arrayref::{array_ref, array_refs};
 use solana_program::program_pack::Pack;
 use solana_program::{
-    account_info::AccountInfo, program_error::ProgramError, program_option::COption, pubkey::Pubkey,
+    account_info::AccountInfo, clock::Clock, program_error::ProgramError, program_option::COption,
+    pubkey::Pubkey, sysvar::Sysvar,
+};
+use spl_token_2022::{
+    extension::{
+        cpi_guard::CpiGuard,
+        memo_transfer,
+        transfer_fee::{TransferFee, TransferFeeConfig},
+        transfer_hook, BaseState, BaseStateWithExtensions, StateWithExtensions,
+    },
+    state::{Account, Mint},
 };
-use spl_token_2022::extension::{BaseState, StateWithExtensions};
 
 pub fn unpack<S: BaseState + Pack>(
     account_data: &[u8],
@@ -85,11 +94,261 @@ pub fn get_mint_decimals(account_info: &AccountInfo) -> Result<u8, ProgramError>
     // so we start at 36.
     let data = account_info.try_borrow_data()?;
 
-    // If we don't check this and an empty account is passed in, we get a panic when
+    // If we don't check this and a shorter account is passed in, we get a panic when
     // we try to index into the data.
-    if data.is_empty() {
+    if data.len() < Mint::LEN {
         return Err(ProgramError::InvalidAccountData);
     }
 
     Ok(data[44])
 }
+
+/// Returns the transfer hook program configured on the mint, if any.
+/// Mints owned by the legacy token program never have one.
+pub fn get_mint_transfer_hook_program_id(
+    mint_info: &AccountInfo,
+) -> Result<Option<Pubkey>, ProgramError> {
+    let data = mint_info.try_borrow_data()?;
+    let mint = unpack::<Mint>(&data)?;
+
+    Ok(transfer_hook::get_program_id(&mint))
+}
+
+/// Returns the mint's TransferFeeConfig extension, if it has one.
+pub fn get_mint_transfer_fee_config(
+    mint_info: &AccountInfo,
+) -> Result<Option<TransferFeeConfig>, ProgramError> {
+    let data = mint_info.try_borrow_data()?;
+    let mint = unpack::<Mint>(&data)?;
+
+    Ok(mint.get_extension::<TransferFeeConfig>().ok().copied())
+}
+
+/// Transfer fee in effect in `epoch`, if the mint charges one.
+fn get_epoch_transfer_fee(
+    mint_info: &AccountInfo,
+    epoch: u64,
+) -> Result<Option<TransferFee>, ProgramError> {
+    let config = get_mint_transfer_fee_config(mint_info)?;
+
+    Ok(config.map(|config| *config.get_epoch_fee(epoch)))
+}
+
+/// Fee withheld by the token program when `amount` is sent with `transfer_checked`
+/// in the current epoch. Returns 0 for mints without the TransferFeeConfig extension.
+pub fn calculate_transfer_fee(mint_info: &AccountInfo, amount: u64) -> Result<u64, ProgramError> {
+    calculate_transfer_fee_for_epoch(mint_info, Clock::get()?.epoch, amount)
+}
+
+/// Same as `calculate_transfer_fee`, for the fee in effect in `epoch`.
+pub fn calculate_transfer_fee_for_epoch(
+    mint_info: &AccountInfo,
+    epoch: u64,
+    amount: u64,
+) -> Result<u64, ProgramError> {
+    match get_epoch_transfer_fee(mint_info, epoch)? {
+        Some(fee) => fee
+            .calculate_fee(amount)
+            .ok_or(ProgramError::ArithmeticOverflow),
+        None => Ok(0),
+    }
+}
+
+/// Smallest amount that has to be sent in the current epoch so that the destination
+/// receives exactly `net_amount` after the transfer fee is withheld.
+pub fn calculate_transfer_gross_amount(
+    mint_info: &AccountInfo,
+    net_amount: u64,
+) -> Result<u64, ProgramError> {
+    calculate_transfer_gross_amount_for_epoch(mint_info, Clock::get()?.epoch, net_amount)
+}
+
+/// Same as `calculate_transfer_gross_amount`, for the fee in effect in `epoch`.
+pub fn calculate_transfer_gross_amount_for_epoch(
+    mint_info: &AccountInfo,
+    epoch: u64,
+    net_amount: u64,
+) -> Result<u64, ProgramError> {
+    match get_epoch_transfer_fee(mint_info, epoch)? {
+        Some(fee) => fee
+            .calculate_pre_fee_amount(net_amount)
+            .ok_or(ProgramError::ArithmeticOverflow),
+        None => Ok(net_amount),
+    }
+}
+
+/// Checks whether the token account has the MemoTransfer extension with
+/// `require_incoming_transfer_memos` enabled.
+pub fn is_memo_transfer_required(token_account_info: &AccountInfo) -> Result<bool, ProgramError> {
+    let data = token_account_info.try_borrow_data()?;
+    let account = unpack::<Account>(&data)?;
+
+    Ok(memo_transfer::memo_required(&account))
+}
+
+/// Checks whether the token account has the CpiGuard extension with `lock_cpi` enabled.
+pub fn is_cpi_guard_enabled(token_account_info: &AccountInfo) -> Result<bool, ProgramError> {
+    let data = token_account_info.try_borrow_data()?;
+    // Legacy token accounts and Token-2022 accounts without extensions can't have it.
+    if data.len() <= Account::LEN {
+        return Ok(false);
+    }
+    let account = unpack::<Account>(&data)?;
+
+    Ok(account
+        .get_extension::<CpiGuard>()
+        .map(|cpi_guard| cpi_guard.lock_cpi.into())
+        .unwrap_or(false))
+}
+
+#[cfg(test)]
+mod tests {
+    use spl_token_2022::extension::{
+        BaseStateWithExtensionsMut, ExtensionType, StateWithExtensionsMut,
+    };
+
+    use super::*;
+
+    fn transfer_fee(epoch: u64, transfer_fee_basis_points: u16, maximum_fee: u64) -> TransferFee {
+        TransferFee {
+            epoch: epoch.into(),
+            maximum_fee: maximum_fee.into(),
+            transfer_fee_basis_points: transfer_fee_basis_points.into(),
+        }
+    }
+
+    #[test]
+    fn transfer_fee_for_epoch() {
+        let space =
+            ExtensionType::try_calculate_account_len::<Mint>(&[ExtensionType::TransferFeeConfig])
+                .unwrap();
+        let mut data = vec![0; space];
+        let mut mint = StateWithExtensionsMut::<Mint>::unpack_uninitialized(&mut data).unwrap();
+        mint.base = Mint {
+            decimals: 0,
+            is_initialized: true,
+            ..Default::default()
+        };
+        mint.pack_base();
+        mint.init_account_type().unwrap();
+        let config = mint.init_extension::<TransferFeeConfig>(true).unwrap();
+        // 1% up to 50 until epoch 10, then 10% up to 1000.
+        config.older_transfer_fee = transfer_fee(0, 100, 50);
+        config.newer_transfer_fee = transfer_fee(10, 1000, 1000);
+
+        let key = Pubkey::new_unique();
+        let mut lamports = 0;
+        let mint_info = AccountInfo::new(
+            &key,
+            false,
+            false,
+            &mut lamports,
+            &mut data,
+            &spl_token_2022::ID,
+            false,
+        );
+
+        assert_eq!(
+            calculate_transfer_fee_for_epoch(&mint_info, 9, 1000),
+            Ok(10)
+        );
+        assert_eq!(
+            calculate_transfer_fee_for_epoch(&mint_info, 9, 10_000),
+            Ok(50)
+        );
+        assert_eq!(
+            calculate_transfer_fee_for_epoch(&mint_info, 10, 1000),
+            Ok(100)
+        );
+        assert_eq!(
+            calculate_transfer_fee_for_epoch(&mint_info, 10, 100_000),
+            Ok(1000)
+        );
+
+        // The fee rounds up, so 1011 - 11 = 1000.
+        assert_eq!(
+            calculate_transfer_gross_amount_for_epoch(&mint_info, 9, 1000),
+            Ok(1011)
+        );
+        assert_eq!(
+            calculate_transfer_gross_amount_for_epoch(&mint_info, 9, 10_000),
+            Ok(10_050)
+        );
+        assert_eq!(
+            calculate_transfer_gross_amount_for_epoch(&mint_info, 10, 900),
+            Ok(1000)
+        );
+        assert_eq!(
+            calculate_transfer_gross_amount_for_epoch(&mint_info, 10, 100_000),
+            Ok(101_000)
+        );
+        assert_eq!(
+            calculate_transfer_gross_amount_for_epoch(&mint_info, 10, u64::MAX),
+            Err(ProgramError::ArithmeticOverflow)
+        );
+    }
+
+    #[test]
+    fn transfer_fee_without_extension() {
+        let key = Pubkey::new_unique();
+        let mut lamports = 0;
+        let mut data = [0; Mint::LEN];
+        Mint {
+            decimals: 0,
+            is_initialized: true,
+            ..Default::default()
+        }
+        .pack_into_slice(&mut data);
+        let mint_info = AccountInfo::new(
+            &key,
+            false,
+            false,
+            &mut lamports,
+            &mut data,
+            &spl_token_2022::ID,
+            false,
+        );
+
+        assert_eq!(calculate_transfer_fee_for_epoch(&mint_info, 0, 1000), Ok(0));
+        assert_eq!(
+            calculate_transfer_gross_amount_for_epoch(&mint_info, 0, 1000),
+            Ok(1000)
+        );
+    }
+
+    #[test]
+    fn get_mint_decimals_rejects_short_accounts() {
+        let key = Pubkey::new_unique();
+        let mut lamports = 0;
+        let mut data = [0; Mint::LEN];
+        data[44] = 6;
+        let mint_info = AccountInfo::new(
+            &key,
+            false,
+            false,
+            &mut lamports,
+            &mut data,
+            &spl_token_2022::ID,
+            false,
+        );
+        assert_eq!(get_mint_decimals(&mint_info), Ok(6));
+
+        for len in [0, 44, 45, Mint::LEN - 1] {
+            let mut lamports = 0;
+            let mut data = vec![6; len];
+            let mint_info = AccountInfo::new(
+                &key,
+                false,
+                false,
+                &mut lamports,
+                &mut data,
+                &spl_token_2022::ID,
+                false,
+            );
+            assert_eq!(
+                get_mint_decimals(&mint_info),
+                Err(ProgramError::InvalidAccountData)
+            );
+        }
+    }
+}